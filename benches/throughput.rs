@@ -1,18 +1,173 @@
 use std::hint::black_box;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use squares::Key;
 
+const KEY: Key = squares::EXAMPLE_KEY;
+
 fn criterion_benchmark(c: &mut Criterion) {
   c.bench_function("squares::u32", |b| b.iter(|| {
-    black_box(squares::u32(black_box(Key::unchecked(0xaf9ed4c87b8e4fa5)), black_box(20)));
+    black_box(squares::u32(black_box(KEY), black_box(20)));
+  }));
+
+  // two rounds instead of three; expect roughly 2/3 of the `u32` time
+  c.bench_function("squares::u32_fast", |b| b.iter(|| {
+    black_box(squares::u32_fast(black_box(KEY), black_box(20)));
   }));
 
   c.bench_function("squares::u64", |b| b.iter(|| {
-    black_box(squares::u64(black_box(Key::unchecked(0xaf9ed4c87b8e4fa5)), black_box(20)))
+    black_box(squares::u64(black_box(KEY), black_box(20)))
   }));
 
   c.bench_function("squares::key", |b| b.iter(|| { black_box(squares::key(black_box(20))); }));
 }
 
-criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+/// `u64_rounds::<R>` for every supported `R`; cost should grow with `R`, `R = 3` matching `squares::u64`
+fn rounds_benchmark(c: &mut Criterion) {
+  fn bench<const R: u32>(group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>) {
+    group.bench_function(BenchmarkId::from_parameter(R), |b| b.iter(|| {
+      black_box(squares::u64_rounds::<R>(black_box(KEY), black_box(20)))
+    }));
+  }
+
+  let mut group = c.benchmark_group("u64_rounds");
+  bench::<2>(&mut group);
+  bench::<3>(&mut group);
+  bench::<4>(&mut group);
+  bench::<5>(&mut group);
+  bench::<6>(&mut group);
+  group.finish();
+}
+
+fn fill_benchmark(c: &mut Criterion) {
+  let mut group = c.benchmark_group("fill_bytes");
+  for len in [64, 4 << 10, 1 << 20] {
+    let mut buf = vec![0u8; len];
+    group.throughput(Throughput::Bytes(len as u64));
+    group.bench_with_input(BenchmarkId::from_parameter(len), &len, |b, _| b.iter(|| {
+      squares::fill_bytes(black_box(KEY), black_box(0), &mut buf);
+      black_box(&buf);
+    }));
+  }
+  group.finish();
+}
+
+/// one key, or four keys a block at a time; the bundle pulls ahead when built with AVX-512DQ/VL (`-C target-cpu=native`)
+fn wide_fill_benchmark(c: &mut Criterion) {
+  const LEN: usize = 1 << 20;
+  let bundle = squares::KeyBundle4::new([squares::key(0), squares::key(1), squares::key(2), squares::key(3)]).unwrap();
+  let mut buf = vec![0u8; LEN];
+
+  let mut group = c.benchmark_group("fill_1MiB");
+  group.throughput(Throughput::Bytes(LEN as u64));
+  group.bench_function("fill_bytes", |b| b.iter(|| {
+    squares::fill_bytes(black_box(KEY), black_box(0), &mut buf);
+    black_box(&buf);
+  }));
+  group.bench_function("fill_bytes_wide", |b| b.iter(|| {
+    squares::fill_bytes_wide(black_box(&bundle), black_box(0), &mut buf);
+    black_box(&buf);
+  }));
+  group.finish();
+}
+
+fn batch_benchmark(c: &mut Criterion) {
+  fn bench<const N: usize>(group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>) {
+    group.throughput(Throughput::Bytes(8 * N as u64));
+    group.bench_function(BenchmarkId::from_parameter(N), |b| b.iter(|| {
+      black_box(squares::u64_array::<N>(black_box(KEY), black_box(20)))
+    }));
+  }
+
+  let mut group = c.benchmark_group("u64_array");
+  bench::<4>(&mut group);
+  bench::<8>(&mut group);
+  bench::<16>(&mut group);
+  group.finish();
+}
+
+/// 1024 keys minted one by one, or batched with `key_array`
+fn key_batch_benchmark(c: &mut Criterion) {
+  const N: usize = 1024;
+  let mut group = c.benchmark_group("keys_1024");
+  group.bench_function("squares::key loop", |b| b.iter(|| {
+    let start = black_box(20);
+    let keys: [Key; N] = core::array::from_fn(|i| squares::key(start + i as u64));
+    black_box(keys)
+  }));
+  group.bench_function("squares::key_array", |b| b.iter(|| {
+    black_box(squares::key_array::<N>(black_box(20)))
+  }));
+  group.finish();
+}
+
+/// relative performance against `rand`'s fast serial generator
+fn small_rng_benchmark(c: &mut Criterion) {
+  const LEN: usize = 4 << 10;
+  let mut buf = vec![0u8; LEN];
+  let mut rng = SmallRng::seed_from_u64(20);
+
+  let mut group = c.benchmark_group("vs_small_rng");
+
+  group.throughput(Throughput::Bytes(8));
+  group.bench_function("squares::u64", |b| {
+    let mut i = 0u64;
+    b.iter(|| {
+      i = i.wrapping_add(1);
+      black_box(squares::u64(black_box(KEY), i))
+    })
+  });
+  group.bench_function("SmallRng::next_u64", |b| b.iter(|| black_box(rng.next_u64())));
+
+  group.throughput(Throughput::Bytes(LEN as u64));
+  group.bench_function("squares::fill_bytes/4096", |b| b.iter(|| {
+    squares::fill_bytes(black_box(KEY), black_box(0), &mut buf);
+    black_box(&buf);
+  }));
+  group.bench_function("SmallRng::fill_bytes/4096", |b| b.iter(|| {
+    rng.fill_bytes(&mut buf);
+    black_box(&buf);
+  }));
+
+  group.finish();
+}
+
+/// cost of reaching `Squares` through `dyn RngCore` instead of a concrete type (with `rand`)
+#[cfg_attr(not(feature = "rand"), allow(unused_variables))]
+fn dispatch_benchmark(c: &mut Criterion) {
+  #[cfg(feature = "rand")] {
+    let mut group = c.benchmark_group("rng_dispatch");
+    group.throughput(Throughput::Bytes(8));
+
+    let mut rng = squares::Squares::with_key(KEY);
+    group.bench_function("static", |b| b.iter(|| black_box(rng.next_u64())));
+
+    let mut boxed: Box<dyn RngCore> = Box::new(squares::Squares::with_key(KEY));
+    let dynamic = black_box(&mut *boxed);
+    group.bench_function("dyn", |b| b.iter(|| black_box(dynamic.next_u64())));
+
+    group.finish();
+  }
+}
+
+/// the AVX-512 kernel behind `fill_bytes_wide`, only present when built with AVX-512DQ/VL
+/// (e.g. `RUSTFLAGS="-C target-cpu=native"` on a CPU that has them)
+#[cfg_attr(not(all(target_arch = "x86_64", target_feature = "avx512dq", target_feature = "avx512vl")), allow(unused_variables))]
+fn simd_benchmark(c: &mut Criterion) {
+  #[cfg(all(target_arch = "x86_64", target_feature = "avx512dq", target_feature = "avx512vl"))] {
+    let bundle = squares::KeyBundle4::new([squares::key(0), squares::key(1), squares::key(2), squares::key(3)]).unwrap();
+    let mut group = c.benchmark_group("simd_fill_bytes_wide");
+    for len in [64, 4 << 10, 1 << 20] {
+      let mut buf = vec![0u8; len];
+      group.throughput(Throughput::Bytes(len as u64));
+      group.bench_with_input(BenchmarkId::from_parameter(len), &len, |b, _| b.iter(|| {
+        squares::fill_bytes_wide(black_box(&bundle), black_box(0), &mut buf);
+        black_box(&buf);
+      }));
+    }
+    group.finish();
+  }
+}
+
+criterion_group!(benches, criterion_benchmark, rounds_benchmark, fill_benchmark, wide_fill_benchmark, batch_benchmark, key_batch_benchmark, small_rng_benchmark, dispatch_benchmark, simd_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+//! Target-independent measurement harness: takes the cycle counter as a function,
+//! so the arithmetic is unit tested on the host (`tests/embedded_harness.rs` in the root crate).
+
+use core::hint::black_box;
+
+/// Cycles elapsed between two readings of a free-running 32-bit counter,
+/// correct across one wraparound.
+#[must_use] #[inline(always)]
+pub fn cycles_between(start: u32, end: u32) -> u32 {
+  end.wrapping_sub(start)
+}
+
+/// Average cycles per call, rounded to nearest: `total` minus the empty-loop `overhead`,
+/// over `iterations` calls. `0` if the overhead swallows the measurement, or for no iterations.
+#[must_use]
+pub fn cycles_per_call(total: u32, overhead: u32, iterations: u32) -> u32 {
+  if iterations == 0 { return 0; }
+  let work = total.saturating_sub(overhead) as u64;
+  ((work + iterations as u64 / 2) / iterations as u64) as u32
+}
+
+/// Times `iterations` calls of `f(i)` with the counter `read`, minus the cost of the loop itself.
+///
+/// The iteration number goes through `black_box` before reaching `f`, and `f` should `black_box`
+/// its result, so the compiler can neither hoist the work out of the loop nor delete it.
+/// Keep `iterations` small enough that the total stays below `2^32` cycles.
+pub fn measure(read: impl Fn() -> u32, iterations: u32, mut f: impl FnMut(u64)) -> u32 {
+  let start = read();
+  for i in 0..iterations { black_box(i as u64); }
+  let overhead = cycles_between(start, read());
+
+  let start = read();
+  for i in 0..iterations { f(black_box(i as u64)); }
+  let total = cycles_between(start, read());
+
+  cycles_per_call(total, overhead, iterations)
+}
@@ -0,0 +1,45 @@
+//! Cycles per call of `u32`, `u64`, `key`, and a 64-byte `fill_bytes`, printed over defmt/RTT.
+//! See README.md for running it.
+
+#![no_std]
+#![no_main]
+
+mod harness;
+
+use core::hint::black_box;
+use cortex_m::peripheral::{Peripherals, DWT};
+use cortex_m_rt::entry;
+use defmt_rtt as _;
+use panic_probe as _;
+
+/// calls per measurement; well below 2^32 cycles in total
+const ITERATIONS: u32 = 1_000;
+
+#[entry]
+fn main() -> ! {
+  let mut cp = Peripherals::take().unwrap();
+  cp.DCB.enable_trace();
+  DWT::unlock();
+  cp.DWT.enable_cycle_counter();
+
+  let read = DWT::cycle_count;
+  let key = squares::EXAMPLE_KEY;
+
+  let u32 = harness::measure(read, ITERATIONS, |i| { black_box(squares::u32(black_box(key), i)); });
+  defmt::println!("squares::u32: {} cycles", u32);
+
+  let u64 = harness::measure(read, ITERATIONS, |i| { black_box(squares::u64(black_box(key), i)); });
+  defmt::println!("squares::u64: {} cycles", u64);
+
+  let key_cycles = harness::measure(read, ITERATIONS, |i| { black_box(squares::key(i)); });
+  defmt::println!("squares::key: {} cycles", key_cycles);
+
+  let mut buf = [0u8; 64];
+  let fill = harness::measure(read, ITERATIONS, |i| {
+    squares::fill_bytes(black_box(key), i, &mut buf);
+    black_box(&buf);
+  });
+  defmt::println!("squares::fill_bytes (64 B): {} cycles", fill);
+
+  loop { cortex_m::asm::bkpt(); }
+}
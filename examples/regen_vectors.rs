@@ -0,0 +1,18 @@
+//! Rewrites `tests/vectors/kat.txt` and `tests/vectors/interkey_collisions.txt`.
+//!
+//! Only run this when an output construction is deliberately versioned;
+//! the `kat` test exists to catch accidental changes.
+
+#[path = "../tests/vectors/generate.rs"]
+mod generate;
+
+fn main() {
+  let files = [
+    (concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/kat.txt"), generate::generate()),
+    (concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/interkey_collisions.txt"), generate::interkey_collisions()),
+  ];
+  for (path, contents) in files {
+    std::fs::write(path, contents).expect("failed to write vectors");
+    println!("wrote {}", path);
+  }
+}
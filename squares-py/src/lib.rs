@@ -0,0 +1,87 @@
+//! Python bindings: the same streams as the Rust crate, bit for bit.
+//!
+//! Keys cross the boundary as plain `int`s (`u64`). Free functions take them unchecked, like
+//! [`Key::unchecked`]; the `Squares` class validates its key.
+
+use numpy::PyReadwriteArray1;
+use pyo3::{exceptions::PyValueError, prelude::*};
+use ::squares::Key;
+
+/// A random admissible key for `index`, as `squares::key`.
+#[pyfunction]
+fn key(index: u64) -> u64 { ::squares::key(index).inner() }
+
+/// `squares::u32`
+#[pyfunction]
+fn u32(key: u64, index: u64) -> u32 { ::squares::u32(Key::unchecked(key), index) }
+
+/// `squares::u64`
+#[pyfunction]
+fn u64(key: u64, index: u64) -> u64 { ::squares::u64(Key::unchecked(key), index) }
+
+/// `squares::f32`, uniform in `[0, 1)`
+#[pyfunction]
+fn f32(key: u64, index: u64) -> f32 { ::squares::f32(Key::unchecked(key), index) }
+
+/// `squares::f64`, uniform in `[0, 1)`
+#[pyfunction]
+fn f64(key: u64, index: u64) -> f64 { ::squares::f64(Key::unchecked(key), index) }
+
+/// `squares::f64_open`, uniform in `(0, 1)`
+#[pyfunction]
+fn f64_open(key: u64, index: u64) -> f64 { ::squares::f64_open(Key::unchecked(key), index) }
+
+/// A `u64` stream under one key: `next()` returns `u64(key, index)` and advances `index`.
+#[pyclass]
+struct Squares {
+  key: Key,
+  #[pyo3(get)]
+  index: u64,
+}
+
+#[pymethods]
+impl Squares {
+  /// Raises `ValueError` if `key` is not admissible.
+  #[new]
+  #[pyo3(signature = (key, index = 0))]
+  fn new(key: u64, index: u64) -> PyResult<Self> {
+    let key = Key::checked(key).map_err(|e| PyValueError::new_err(e.message()))?;
+    Ok(Self { key, index })
+  }
+
+  #[getter]
+  fn key(&self) -> u64 { self.key.inner() }
+
+  fn next(&mut self) -> u64 {
+    let v = ::squares::u64(self.key, self.index);
+    self.index = self.index.wrapping_add(1);
+    v
+  }
+
+  /// Advances the stream by `n` outputs without producing them.
+  fn skip(&mut self, n: u64) { self.index = self.index.wrapping_add(n); }
+
+  /// Fills a contiguous `uint64` array with the next `len(out)` outputs (as `squares::fill_u64`),
+  /// without holding the GIL. A non-contiguous array (e.g. a strided view) raises `ValueError`.
+  fn fill(&mut self, py: Python<'_>, mut out: PyReadwriteArray1<'_, u64>) -> PyResult<()> {
+    let out = out.as_slice_mut().map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let (key, start) = (self.key, self.index);
+    py.allow_threads(|| ::squares::fill_u64(key, start, out));
+    self.index = start.wrapping_add(out.len() as u64);
+    Ok(())
+  }
+}
+
+#[pymodule]
+#[pyo3(name = "squares")]
+fn squares_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  // `self::` tells the functions named like primitive types apart from the types
+  m.add_function(wrap_pyfunction!(self::key, m)?)?;
+  m.add_function(wrap_pyfunction!(self::u32, m)?)?;
+  m.add_function(wrap_pyfunction!(self::u64, m)?)?;
+  m.add_function(wrap_pyfunction!(self::f32, m)?)?;
+  m.add_function(wrap_pyfunction!(self::f64, m)?)?;
+  m.add_function(wrap_pyfunction!(self::f64_open, m)?)?;
+  m.add_class::<Squares>()?;
+  Ok(())
+}
@@ -0,0 +1,76 @@
+"""Checks the bindings against the Rust crate's known-answer vectors (tests/vectors/kat.txt)."""
+
+import pathlib
+import struct
+
+import numpy as np
+import pytest
+
+import squares
+
+KAT = pathlib.Path(__file__).resolve().parents[2] / "tests" / "vectors" / "kat.txt"
+
+
+def vectors(kind):
+    for line in KAT.read_text().splitlines():
+        fields = line.split()
+        if fields and fields[0] == kind:
+            yield [int(f, 16) for f in fields[1:]]
+
+
+def f32_bits(x):
+    return struct.unpack("<I", struct.pack("<f", x))[0]
+
+
+def f64_bits(x):
+    return struct.unpack("<Q", struct.pack("<d", x))[0]
+
+
+@pytest.mark.parametrize(
+    "kind, f",
+    [
+        ("u32", squares.u32),
+        ("u64", squares.u64),
+        ("f32", lambda k, i: f32_bits(squares.f32(k, i))),
+        ("f64", lambda k, i: f64_bits(squares.f64(k, i))),
+        ("f64_open", lambda k, i: f64_bits(squares.f64_open(k, i))),
+    ],
+)
+def test_outputs_match_rust(kind, f):
+    rows = list(vectors(kind))
+    assert rows
+    for key, index, expected in rows:
+        assert f(key, index) == expected
+
+
+def test_keys_match_rust():
+    rows = list(vectors("key"))
+    assert rows
+    for index, expected in rows:
+        assert squares.key(index) == expected
+
+
+def test_class_stream():
+    k = squares.key(7)
+    rng = squares.Squares(k, 2**64 - 1)
+    assert rng.next() == squares.u64(k, 2**64 - 1)
+    assert rng.next() == squares.u64(k, 0)
+    rng.skip(10)
+    assert rng.index == 11
+    assert rng.next() == squares.u64(k, 11)
+
+    with pytest.raises(ValueError):
+        squares.Squares(0)
+
+
+def test_numpy_fill_matches_fill_u64():
+    k = squares.key(3)
+    rng = squares.Squares(k, 5)
+    out = np.zeros(1_000_000, dtype=np.uint64)
+    rng.fill(out)
+    assert rng.index == 5 + len(out)
+    expected = np.fromiter((squares.u64(k, 5 + i) for i in range(len(out))), dtype=np.uint64)
+    assert np.array_equal(out, expected)
+
+    with pytest.raises(ValueError):
+        rng.fill(np.zeros(10, dtype=np.uint64)[::2])
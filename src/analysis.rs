@@ -0,0 +1,595 @@
+//! # Analysis
+//!
+//! Statistical tooling for auditing the generator (feature `analysis`).
+//! Everything here is deterministic, so results are reproducible from the inputs alone.
+
+#[cfg(feature = "alloc")] use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")] use core::ops::Range;
+use crate::{key, keys_distinct, u64, Key};
+
+/// the key used to pick the sampled counters
+const SAMPLE_KEY: Key = Key::unchecked(0xd48d75d68473c2a9);
+
+/// Flip counts between every counter bit and every output bit.
+#[derive(Clone, Debug)]
+pub struct AvalancheReport {
+  /// number of sampled counters
+  pub samples: u64,
+  /// `flips[i][j]`: how many samples flipped output bit `j` when counter bit `i` was flipped
+  pub flips: [[u64; 64]; 64],
+}
+
+impl AvalancheReport {
+  /// Observed probability that flipping counter bit `counter_bit` flips output bit `output_bit`.
+  /// Ideally `0.5`.
+  #[must_use]
+  pub fn probability(&self, counter_bit: usize, output_bit: usize) -> f64 {
+    self.flips[counter_bit][output_bit] as f64 / self.samples as f64
+  }
+
+  /// The largest `|p - 0.5|` over the whole matrix.
+  ///
+  /// Sampling noise alone gives roughly `0.5 / sqrt(samples)` per entry, and about four times
+  /// that as the worst of 4096 entries; for the canonical generator with `20_000` samples this
+  /// stays below `0.02`.
+  #[must_use]
+  pub fn worst_deviation(&self) -> f64 {
+    let mut worst: f64 = 0.0;
+    for i in 0..64 {
+      for j in 0..64 {
+        worst = worst.max((self.probability(i, j) - 0.5).abs());
+      }
+    }
+    worst
+  }
+}
+
+/// Measures the avalanche matrix of [`u64()`] under `key`, over `samples` pseudo-random counters.
+#[must_use]
+pub fn avalanche(key: Key, samples: u64) -> AvalancheReport {
+  avalanche_of(u64, key, samples)
+}
+
+/// Measures the avalanche matrix of any `(key, counter) -> u64` function,
+/// e.g. a reduced-round variant.
+#[must_use]
+pub fn avalanche_of(f: fn(Key, u64) -> u64, key: Key, samples: u64) -> AvalancheReport {
+  let mut report = AvalancheReport { samples, flips: [[0; 64]; 64] };
+
+  for s in 0..samples {
+    let counter = u64(SAMPLE_KEY, s);
+    let base = f(key, counter);
+
+    for (i, row) in report.flips.iter_mut().enumerate() {
+      let diff = base ^ f(key, counter ^ (1 << i));
+      for (j, count) in row.iter_mut().enumerate() {
+        *count += (diff >> j) & 1;
+      }
+    }
+  }
+
+  report
+}
+
+/// Measures the bias of every output bit of [`u64()`] under `key`, over counters `0..samples`.
+/// Entry `j` is the observed frequency of ones at bit `j`, minus `0.5`.
+#[must_use]
+pub fn bit_bias(key: Key, samples: u64) -> [f64; 64] {
+  bit_bias_of(u64, key, samples)
+}
+
+/// Measures per-bit output bias of any `(key, counter) -> u64` function (see [`bit_bias`]).
+#[must_use]
+pub fn bit_bias_of(f: fn(Key, u64) -> u64, key: Key, samples: u64) -> [f64; 64] {
+  let mut ones = [0u64; 64];
+  for c in 0..samples {
+    let x = f(key, c);
+    for (j, count) in ones.iter_mut().enumerate() {
+      *count += (x >> j) & 1;
+    }
+  }
+  ones.map(|n| n as f64 / samples as f64 - 0.5)
+}
+
+/// Tallies the popcount of `n` outputs `u64(key, start_index..)`: `buckets[c]` is incremented
+/// for every output with `c` ones. Counts are added to `buckets`, so runs can be accumulated.
+///
+/// A good key gives a Binomial(64, 1/2) shape centered at 32; a broken one is visibly skewed.
+pub fn popcount_distribution(key: Key, start_index: u64, n: usize, buckets: &mut [u64; 65]) {
+  for i in 0..n as u64 {
+    buckets[u64(key, start_index.wrapping_add(i)).count_ones() as usize] += 1;
+  }
+}
+
+/// Result of a [`birthday`] collision audit.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub struct BirthdayReport {
+  /// number of outputs drawn
+  pub n: u64,
+  /// width the outputs were truncated to
+  pub bits: u32,
+  /// outputs whose truncated value had already been seen
+  pub observed: u64,
+  /// estimated Bloom filter false positives included in `observed` (`0.0` when exact)
+  pub false_positives: f64,
+  /// expected collisions for an ideal generator
+  pub expected: f64,
+  /// standard score of `observed` against an ideal generator (with `false_positives` added to its
+  /// mean and variance); strongly positive values mean too many collisions
+  pub z_score: f64,
+}
+
+/// the keys used to hash values into the Bloom filter
+#[cfg(feature = "alloc")]
+const BLOOM_KEYS: [Key; 2] = [Key::unchecked(0x67778f85bc349281), Key::unchecked(0x3597e186fcd73281)];
+/// Bloom filter bits per drawn output
+#[cfg(feature = "alloc")]
+const BLOOM_BITS_PER_ITEM: u64 = 24;
+/// Bloom filter probes per value
+#[cfg(feature = "alloc")]
+const BLOOM_PROBES: u64 = 12;
+
+/// A Bloom filter over `u64`s, sized for a number of items
+#[cfg(feature = "alloc")]
+struct Bloom {
+  words: Vec<u64>,
+  /// size in bits
+  m: u64,
+  /// bits set
+  set: u64,
+}
+
+#[cfg(feature = "alloc")]
+impl Bloom {
+  fn new(items: u64) -> Self {
+    let m = (items.max(1) * BLOOM_BITS_PER_ITEM).div_ceil(64) * 64;
+    Self { words: vec![0; (m / 64) as usize], m, set: 0 }
+  }
+
+  /// the chance a value not inserted is reported present, estimated from the fill
+  fn false_positive_rate(&self) -> f64 {
+    libm::pow(self.set as f64 / self.m as f64, BLOOM_PROBES as f64)
+  }
+
+  /// the probed bits of `v`, as `(word, mask)`
+  fn probes(&self, v: u64) -> impl Iterator<Item = (usize, u64)> {
+    let (h1, h2, m) = (u64(BLOOM_KEYS[0], v), u64(BLOOM_KEYS[1], v) | 1, self.m);
+    (0..BLOOM_PROBES).map(move |p| {
+      let pos = ((h1.wrapping_add(p.wrapping_mul(h2)) as u128 * m as u128) >> 64) as u64;
+      ((pos / 64) as usize, 1 << (pos % 64))
+    })
+  }
+
+  fn contains(&self, v: u64) -> bool {
+    self.probes(v).all(|(word, bit)| self.words[word] & bit != 0)
+  }
+
+  /// Inserts `v`, returning whether it was (apparently) present already.
+  fn insert(&mut self, v: u64) -> bool {
+    let mut present = true;
+    for (word, bit) in self.probes(v) {
+      if self.words[word] & bit == 0 {
+        present = false;
+        self.words[word] |= bit;
+        self.set += 1;
+      }
+    }
+    present
+  }
+}
+
+/// Draws `u64(key, 0..n)`, truncates each to its upper `bits` bits, and counts collisions
+/// (draws whose truncated value was already seen), comparing against the birthday expectation.
+///
+/// For `bits <= 32` an exact bitset of `2^bits` bits is used (at most 512 MiB).
+/// Wider outputs go through a Bloom filter of 3 bytes per draw; its (small) false positive
+/// count is estimated from the filter's fill and reported separately.
+///
+/// ## Panics
+/// If `bits` is not in `1..=64`, or the bitset's `2^bits / 64` words exceed `usize::MAX`
+/// (only possible on 16-bit targets, for `bits` in `22..=32`).
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn birthday(key: Key, n: u64, bits: u32) -> BirthdayReport {
+  assert!((1..=64).contains(&bits), "birthday: bits must be in 1..=64");
+  let truncate = |c: u64| u64(key, c) >> (64 - bits);
+
+  let mut observed = 0;
+  let mut false_positives = 0.0;
+
+  if bits <= 32 {
+    // sized in u64: `1usize << 32` would overflow on 32-bit targets
+    let words = usize::try_from((1u64 << bits).div_ceil(64)).expect("birthday: bitset too large for this target");
+    let mut seen = vec![0u64; words];
+    for c in 0..n {
+      let v = truncate(c) as usize;
+      let (word, bit) = (v / 64, 1 << (v % 64));
+      if seen[word] & bit != 0 { observed += 1; }
+      seen[word] |= bit;
+    }
+  } else {
+    let mut filter = Bloom::new(n);
+    for c in 0..n {
+      false_positives += filter.false_positive_rate();
+      if filter.insert(truncate(c)) { observed += 1; }
+    }
+  }
+
+  let (expected, variance) = birthday_moments(n, bits);
+  let z_score = (observed as f64 - expected - false_positives) / libm::sqrt(variance + false_positives);
+  BirthdayReport { n, bits, observed, false_positives, expected, z_score }
+}
+
+/// Mean and variance of the collision count of `n` ideal draws from `2^bits` values.
+///
+/// With `M = 2^bits`, `a = (1 - 1/M)^n`, `b = (1 - 2/M)^n`:
+/// `E = n - M(1 - a)` and `Var = M^2 (b - a^2) + M (a - b)`,
+/// rearranged around `expm1`/`ln1p` so they stay accurate for `M` up to `2^64`.
+#[cfg(feature = "alloc")]
+fn birthday_moments(n: u64, bits: u32) -> (f64, f64) {
+  use libm::{exp, expm1, log1p as ln1p};
+
+  let n = n as f64;
+  let m = libm::ldexp(1.0, bits as i32);
+  let l = ln1p(-1.0 / m);
+
+  // expm1(y) - y, which cancels badly for small y
+  let g = |y: f64| if y.abs() < 1e-2 {
+    y * y * (1.0 / 2.0 + y * (1.0 / 6.0 + y * (1.0 / 24.0 + y / 120.0)))
+  } else {
+    expm1(y) - y
+  };
+  // 1 + M ln(1 - 1/M)
+  let c = if bits > 20 { -(0.5 + 1.0 / (3.0 * m)) / m } else { 1.0 + m * l };
+
+  let expected = n * c + m * g(n * l);
+
+  let a2 = exp(2.0 * n * l);
+  let b = exp(n * ln1p(-2.0 / m));
+  let b_minus_a2 = a2 * expm1(n * ln1p(-1.0 / ((m - 1.0) * (m - 1.0))));
+  let a_minus_b = b * expm1(n * ln1p(1.0 / (m - 2.0)));
+  let variance = if bits == 1 {
+    // M - 2 = 0: b is exactly 0 for n > 0
+    m * m * (b - a2) + m * (exp(n * l) - b)
+  } else {
+    m * m * b_minus_a2 + m * a_minus_b
+  };
+
+  (expected, variance)
+}
+
+/// Approximate probability that `n` ideal 64-bit outputs contain at least one repeated value,
+/// `1 - exp(-n(n - 1) / 2^65)`: about `0.39` at `n = 2^32`, and `n^2 / 2^65` when that is small.
+#[must_use]
+pub fn birthday_collision_prob(n: u64) -> f64 {
+  let n = n as f64;
+  -libm::expm1(-n * (n - 1.0) / libm::ldexp(1.0, 65))
+}
+
+/// Mean and (population) variance of `n` uniform draws [`f64()`](crate::f64) at `start_index..`,
+/// accumulated with Welford's numerically stable single-pass update.
+/// Ideally `0.5` and `1/12`. Both are `0.0` when `n == 0`.
+#[must_use]
+pub fn welford_stats(key: Key, start_index: u64, n: usize) -> (f64, f64) {
+  let mut mean = 0.0;
+  let mut m2 = 0.0;
+  for i in 0..n {
+    let x = crate::f64(key, start_index.wrapping_add(i as u64));
+    let delta = x - mean;
+    mean += delta / (i + 1) as f64;
+    m2 += delta * (x - mean);
+  }
+  if n == 0 { return (0.0, 0.0); }
+  (mean, m2 / n as f64)
+}
+
+/// Pearson correlations between two streams at a range of lags; see [`cross_correlation`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct CrossCorrelationReport {
+  /// number of output pairs per lag
+  pub samples: u64,
+  /// the lags measured
+  pub lags: Range<i64>,
+  /// `correlations[i]`: correlation at lag `lags.start + i`
+  pub correlations: Vec<f64>,
+}
+
+#[cfg(feature = "alloc")]
+impl CrossCorrelationReport {
+  /// The correlation at `lag`.
+  ///
+  /// ## Panics
+  /// If `lag` is outside [`lags`](Self::lags).
+  #[must_use]
+  pub fn correlation(&self, lag: i64) -> f64 {
+    assert!(self.lags.contains(&lag), "correlation: lag not measured");
+    self.correlations[(lag - self.lags.start) as usize]
+  }
+
+  /// The largest `|r| * sqrt(samples)` over all lags.
+  ///
+  /// For independent streams each entry is close to a standard normal, so this exceeds `4.5`
+  /// with probability about `7e-6` per lag; overlapping or related streams give values in the
+  /// hundreds (exactly `sqrt(samples)` where the streams coincide).
+  #[must_use]
+  pub fn max_abs_z(&self) -> f64 {
+    let scale = libm::sqrt(self.samples as f64);
+    self.correlations.iter().fold(0.0, |worst: f64, r| worst.max(r.abs() * scale))
+  }
+}
+
+/// Measures the correlation of [`u64()`] streams under keys `a` and `b`: at each lag `l`,
+/// between the uniforms `f64(a, i)` and `f64(b, i + l)` (counters wrapping) for `i` in `0..samples`.
+///
+/// Block partitioning of one key is `a == b` with lags around the block length.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn cross_correlation(a: Key, b: Key, lags: Range<i64>, samples: u64) -> CrossCorrelationReport {
+  cross_correlation_of(|i| u64(a, i), |i| u64(b, i), lags, samples)
+}
+
+/// Measures cross-correlation between any two counter-indexed streams (see [`cross_correlation`]),
+/// e.g. sub-counters of one key.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn cross_correlation_of(a: impl Fn(u64) -> u64, b: impl Fn(u64) -> u64, lags: Range<i64>, samples: u64) -> CrossCorrelationReport {
+  let unit = |v: u64| (v >> 11) as f64 * (1.0 / (1u64 << 53) as f64);
+  let n = samples as f64;
+
+  let correlations = lags.clone().map(|lag| {
+    let (mut sx, mut sy, mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for i in 0..samples {
+      let x = unit(a(i));
+      let y = unit(b(i.wrapping_add(lag as u64)));
+      sx += x; sy += y;
+      sxx += x * x; syy += y * y; sxy += x * y;
+    }
+    let cov = sxy / n - (sx / n) * (sy / n);
+    let vx = sxx / n - (sx / n) * (sx / n);
+    let vy = syy / n - (sy / n) * (sy / n);
+    cov / libm::sqrt(vx * vy)
+  }).collect();
+
+  CrossCorrelationReport { samples, lags, correlations }
+}
+
+/// Result of a [`stream_overlap`] audit.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub struct OverlapReport {
+  /// number of streams
+  pub streams: usize,
+  /// outputs drawn from each stream
+  pub per_stream: u64,
+  /// outputs that had already appeared in an earlier stream
+  pub observed: u64,
+  /// estimated Bloom filter false positives included in `observed`
+  pub false_positives: f64,
+  /// expected inter-stream collisions for independent ideal streams
+  pub expected: f64,
+  /// standard score of `observed` against `expected + false_positives` (as Poisson);
+  /// strongly positive values mean the streams share outputs
+  pub z_score: f64,
+}
+
+/// Draws `per_stream` outputs [`u64()`] from each `(key, start_counter)` stream and counts outputs
+/// already produced by an earlier stream (repeats within one stream are not counted),
+/// through a Bloom filter of 3 bytes per output.
+///
+/// Overlapping counter ranges of one key show up as `per_stream`-sized excesses.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn stream_overlap(streams: &[(Key, u64)], per_stream: u64) -> OverlapReport {
+  let streams: Vec<_> = streams.iter().map(|&(k, start)| move |i: u64| u64(k, start.wrapping_add(i))).collect();
+  let refs: Vec<&dyn Fn(u64) -> u64> = streams.iter().map(|s| s as &dyn Fn(u64) -> u64).collect();
+  stream_overlap_of(&refs, per_stream)
+}
+
+/// Overlap audit of any counter-indexed streams (see [`stream_overlap`]); stream `s` produces
+/// `streams[s](i)` for `i` in `0..per_stream`.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn stream_overlap_of(streams: &[&dyn Fn(u64) -> u64], per_stream: u64) -> OverlapReport {
+  let mut filter = Bloom::new(per_stream * streams.len() as u64);
+  let (mut observed, mut false_positives, mut expected) = (0, 0.0, 0.0);
+
+  for (s, stream) in streams.iter().enumerate() {
+    // check the whole stream against earlier ones before adding it
+    false_positives += per_stream as f64 * filter.false_positive_rate();
+    observed += (0..per_stream).filter(|&i| filter.contains(stream(i))).count() as u64;
+    for i in 0..per_stream { filter.insert(stream(i)); }
+    expected += per_stream as f64 * (s as u64 * per_stream) as f64 / libm::ldexp(1.0, 64);
+  }
+
+  let mean = expected + false_positives;
+  let z_score = (observed as f64 - mean) / libm::sqrt(mean.max(f64::MIN_POSITIVE));
+  OverlapReport { streams: streams.len(), per_stream, observed, false_positives, expected, z_score }
+}
+
+/// Counts violations of the inter-key rule (rule 5 of [`Key`]) between keys made by [`key`]:
+/// over `sample` pairs of pseudo-random indices, plus `sample` adjacent pairs `(i, i + 1)`,
+/// the realistic case of keys minted from consecutive seeds.
+///
+/// `key` does not enforce rule 5 across indices. An exhaustive search of indices below `100_000`
+/// finds about 1 colliding pair in `3 * 10^8` (listed in `tests/vectors/interkey_collisions.txt`),
+/// and adjacent indices have shown none in `10^7`. Use [`crate::key_set`] when the rule must hold.
+#[must_use]
+pub fn interkey_collisions(sample: u64) -> u64 {
+  let mut collisions = 0;
+  for s in 0..sample {
+    let a = u64(SAMPLE_KEY, 2 * s);
+    let b = u64(SAMPLE_KEY, 2 * s + 1);
+    collisions += !keys_distinct(key(a), key(b)) as u64;
+    collisions += !keys_distinct(key(a), key(a.wrapping_add(1))) as u64;
+  }
+  collisions
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, primitives::{init, round, sq}, u64_rounds, Key};
+  use super::{avalanche, avalanche_of, bit_bias, bit_bias_of, interkey_collisions, popcount_distribution, welford_stats};
+
+  /// Squares cut down to a single round
+  fn one_round(key: Key, counter: u64) -> u64 {
+    let (x, y, z) = init(key, counter);
+    sq(round(x, y), z)
+  }
+
+  /// the reduced-round `u32_fast`, widened to fit the audits
+  fn u32_fast_wide(key: Key, counter: u64) -> u64 { crate::u32_fast(key, counter) as u64 }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn u32_fast_avalanche() {
+    // only the low 32 output bits are live
+    let report = avalanche_of(u32_fast_wide, key(1), 20_000);
+    for i in 0..64 {
+      for j in 0..32 {
+        assert!((report.probability(i, j) - 0.5).abs() < 0.02, "{} {}", i, j);
+      }
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn u32_fast_bit_bias() {
+    for b in &bit_bias_of(u32_fast_wide, key(2), 100_000)[..32] {
+      assert!(b.abs() < 0.01, "{}", b);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn canonical_avalanche() {
+    let report = avalanche(key(1), 20_000);
+    assert!(report.worst_deviation() < 0.02, "{}", report.worst_deviation());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn weakened_avalanche_fails() {
+    let report = avalanche_of(one_round, key(1), 20_000);
+    assert!(report.worst_deviation() >= 0.02, "{}", report.worst_deviation());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn round_counts_pass_audits() {
+    fn audit(f: fn(Key, u64) -> u64) -> (f64, f64) {
+      let bias = bit_bias_of(f, key(2), 100_000).iter().fold(0f64, |m, b| m.max(b.abs()));
+      (bias, avalanche_of(f, key(1), 20_000).worst_deviation())
+    }
+
+    // two rounds is the floor: not separable from three by these audits, unlike one round
+    for (r, f) in [(2, u64_rounds::<2> as fn(Key, u64) -> u64), (3, u64_rounds::<3>), (4, u64_rounds::<4>), (5, u64_rounds::<5>), (6, u64_rounds::<6>)] {
+      let (bias, aval) = audit(f);
+      assert!(bias < 0.01 && aval < 0.02, "R = {}: {} {}", r, bias, aval);
+    }
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn birthday_matches_enumeration() {
+    use std::collections::BTreeSet;
+    use crate::u64;
+    use super::birthday;
+
+    let k = key(3);
+    for (n, bits) in [(0, 8), (1, 1), (100, 1), (300, 8), (2_000, 12), (5_000, 20), (3_000, 40), (3_000, 64)] {
+      let mut seen = BTreeSet::new();
+      let collisions = (0..n).filter(|&c| !seen.insert(u64(k, c) >> (64 - bits))).count() as u64;
+      let r = birthday(k, n, bits);
+      assert_eq!(r.observed, collisions, "n = {}, bits = {}", n, bits);
+    }
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn birthday_expectation() {
+    use super::birthday_moments;
+
+    // exhaustive: 2 draws from 2 values collide with probability 1/2
+    let (e, v) = birthday_moments(2, 1);
+    assert!((e - 0.5).abs() < 1e-12 && (v - 0.25).abs() < 1e-12, "{} {}", e, v);
+
+    // rare collisions are ~Poisson(n^2 / 2M)
+    let (e, v) = birthday_moments(1 << 20, 64);
+    let poisson = (1u64 << 20) as f64 * ((1 << 20) - 1) as f64 / 2.0 / 2f64.powi(64);
+    assert!((e / poisson - 1.0).abs() < 1e-6 && (v / poisson - 1.0).abs() < 1e-3, "{} {} {}", e, v, poisson);
+
+    // n = 2^32 draws from 2^32 values: n/e distinct values are missed
+    let (e, _) = birthday_moments(1 << 32, 32);
+    assert!((e / (2f64.powi(32) / core::f64::consts::E) - 1.0).abs() < 1e-6, "{}", e);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn birthday_canonical_unremarkable() {
+    use super::birthday;
+
+    for (n, bits) in [(100_000, 24), (300_000, 32), (2_000_000, 40)] {
+      let r = birthday(key(4), n, bits);
+      assert!(r.z_score.abs() < 4.0, "{:?}", r);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn popcount_peaks_at_32() {
+    let mut buckets = [0; 65];
+    popcount_distribution(key(5), 0, 50_000, &mut buckets);
+    popcount_distribution(key(5), 50_000, 50_000, &mut buckets);
+    assert_eq!(buckets.iter().sum::<u64>(), 100_000);
+
+    let peak = (0..65).max_by_key(|&c| buckets[c]).unwrap();
+    assert!((31..=33).contains(&peak), "{:?}", buckets);
+
+    // P(popcount = 32) for Binomial(64, 1/2) is ~0.0993
+    assert!((9_000..10_900).contains(&buckets[32]), "{:?}", buckets);
+    assert!(buckets[..16].iter().chain(&buckets[49..]).sum::<u64>() < 10);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn canonical_bit_bias() {
+    for b in bit_bias(key(2), 100_000) {
+      assert!(b.abs() < 0.01, "{}", b);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn interkey_collisions_below_bound() {
+    // at the documented ~1 in 3e8 rate, 400k pairs should find none
+    assert_eq!(interkey_collisions(200_000), 0);
+  }
+
+  #[test]
+  fn birthday_collision_prob_known_values() {
+    use super::birthday_collision_prob;
+
+    assert_eq!(birthday_collision_prob(0), 0.0);
+    assert_eq!(birthday_collision_prob(1), 0.0);
+    assert!((birthday_collision_prob(1 << 32) - 0.393_469).abs() < 1e-6);
+    // 2^-64 for a single pair
+    assert!((birthday_collision_prob(2) * libm::ldexp(1.0, 64) - 1.0).abs() < 1e-12);
+    assert!(birthday_collision_prob(1 << 36) > 0.999_999);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn welford_uniform_moments() {
+    let (mean, var) = welford_stats(key(7), 1 << 40, 1_000_000);
+    // standard errors are ~3e-4 and ~9e-5
+    assert!((mean - 0.5).abs() < 2e-3, "{}", mean);
+    assert!((var - 1.0 / 12.0).abs() < 1e-3, "{}", var);
+
+    assert_eq!(welford_stats(key(7), 0, 0), (0.0, 0.0));
+    let (one, zero) = welford_stats(key(7), 3, 1);
+    assert_eq!((one, zero), (crate::f64(key(7), 3), 0.0));
+  }
+}
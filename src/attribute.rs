@@ -0,0 +1,119 @@
+use crate::{f64, fold::{fold_bytes, fold_step}, u64, uniform, Key, UniformInt};
+
+/// the state attribute counters start from, separating them from other folds
+const ATTRIBUTE_DOMAIN: u64 = fold_bytes(b"squares::attribute");
+
+/// The counter for attribute `tag` of `entity_id`: the entity, then the tag's hash, folded in order.
+#[must_use] #[inline(always)]
+const fn attribute_index(entity_id: u64, tag: &str) -> u64 {
+  fold_step(fold_step(ATTRIBUTE_DOMAIN, entity_id), fold_bytes(tag.as_bytes()))
+}
+
+/// Produces a stable, independent `u64` per (entity, attribute) pair, e.g. `attribute(key, id, "hp roll")`,
+/// so many attributes of one entity need no hand-spaced counters.
+///
+/// The entity id and a hash of `tag` are mixed into a counter for [`u64()`]; the mapping is frozen
+/// and position-free, so adding a tag never moves the others. Distinct pairs only share a counter
+/// by chance (birthday-bound in 64 bits), and different tags of one entity are unrelated draws.
+#[must_use] #[inline(always)]
+pub const fn attribute(key: Key, entity_id: u64, tag: &str) -> u64 {
+  u64(key, attribute_index(entity_id, tag))
+}
+
+/// Produces a uniformly distributed `f64` in `[0, 1)` for an (entity, attribute) pair,
+/// as [`f64()`] at the counter [`attribute`] uses.
+#[must_use] #[inline(always)]
+pub fn attribute_f64(key: Key, entity_id: u64, tag: &str) -> f64 {
+  f64(key, attribute_index(entity_id, tag))
+}
+
+/// Produces a uniformly distributed integer in `lo..=hi` for an (entity, attribute) pair,
+/// as [`uniform`] at the counter [`attribute`] uses: `let hp: u16 = attribute_range(key, id, "hp roll", 20, 40);`
+///
+/// ## Panics
+/// If `lo > hi`.
+#[must_use] #[inline(always)]
+pub fn attribute_range<T: UniformInt>(key: Key, entity_id: u64, tag: &str, lo: T, hi: T) -> T {
+  uniform(key, attribute_index(entity_id, tag), lo, hi)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u64, EXAMPLE_KEY};
+  use super::{attribute, attribute_f64, attribute_range};
+
+  #[test]
+  fn attribute_frozen() {
+    let got = [
+      attribute(EXAMPLE_KEY, 0, "hp roll"),
+      attribute(EXAMPLE_KEY, 0, "loot seed"),
+      attribute(EXAMPLE_KEY, 1, "hp roll"),
+      attribute(EXAMPLE_KEY, u64::MAX, ""),
+    ];
+    assert_eq!(got, [0xc81243796db53a1f, 0x1fd16da443b61e50, 0x792328f4c8e76aa9, 0xab9cef2952326090]);
+    assert_eq!(attribute_range(EXAMPLE_KEY, 7, "hp roll", 20u16, 40), 20);
+  }
+
+  #[test]
+  fn attribute_typed_forms_agree() {
+    let k = key(3);
+    for id in 0..100 {
+      let v = attribute_f64(k, id, "color");
+      assert!((0.0..1.0).contains(&v));
+      assert_eq!(v, (attribute(k, id, "color") >> 11) as f64 * (1.0 / (1u64 << 53) as f64));
+
+      let r: u8 = attribute_range(k, id, "hp roll", 10, 20);
+      assert!((10..=20).contains(&r));
+      assert_eq!(attribute_range(k, id, "hp roll", 5i32, 5), 5);
+    }
+  }
+
+  #[test]
+  fn attribute_separates_tags_and_entities() {
+    let k = key(5);
+    assert_ne!(attribute(k, 0, "a"), attribute(k, 0, "b"));
+    assert_ne!(attribute(k, 0, "hp"), attribute(k, 0, "hp\0"));
+    assert_ne!(attribute(k, 0, "hp"), attribute(k, 1, "hp"));
+    assert_ne!(attribute(k, 0, "hp"), attribute(key(6), 0, "hp"));
+    assert!((0..1_000).all(|i| attribute(k, i, "hp") != u64(k, i)));
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn attribute_tags_uncorrelated() {
+    let k = key(11);
+    let n = if cfg!(miri) { 500 } else { 20_000 };
+    for (a, b) in [("hp roll", "loot seed"), ("a", "b"), ("color", "color ")] {
+      let (mut sx, mut sy, mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+      for id in 0..n {
+        let x = attribute_f64(k, id, a);
+        let y = attribute_f64(k, id, b);
+        sx += x; sy += y; sxx += x * x; syy += y * y; sxy += x * y;
+      }
+      let n = n as f64;
+      let cov = sxy / n - sx / n * sy / n;
+      let r = cov / ((sxx / n - (sx / n).powi(2)) * (syy / n - (sy / n).powi(2))).sqrt();
+      // the standard error of r is about 1/sqrt(n) = 0.007
+      assert!(r.abs() < 0.03, "{:?}/{:?}: r = {}", a, b, r);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn attribute_collisions_at_birthday_level() {
+    const TAGS: [&str; 16] = [
+      "hp roll", "loot seed", "color", "name", "x", "y", "speed", "armor",
+      "a", "b", "ab", "ba", "", " ", "hp", "hp\0",
+    ];
+    const ENTITIES: usize = 1 << 10;
+    let k = key(13);
+    // 2^14 pairs truncated to 24 bits: C(2^14, 2) / 2^24, about 8 collisions expected
+    let mut values = [0u32; TAGS.len() * ENTITIES];
+    for (i, v) in values.iter_mut().enumerate() {
+      *v = (attribute(k, (i / TAGS.len()) as u64, TAGS[i % TAGS.len()]) >> 40) as u32;
+    }
+    values.sort_unstable();
+    let collisions = values.windows(2).filter(|w| w[0] == w[1]).count();
+    assert!((1..=24).contains(&collisions), "{} collisions", collisions);
+  }
+}
@@ -0,0 +1,86 @@
+//! Mints and verifies admissible Squares keys.
+//!
+//! ```text
+//! squares-keygen [--count N] [--start-index I] [--format hex|dec] [--distinct]
+//! squares-keygen --verify <hex>
+//! ```
+//!
+//! Exit codes: `0` success, `1` the verified key is inadmissible, `2` bad arguments.
+
+use std::process::ExitCode;
+use squares::{key, key_set, Key};
+
+const USAGE: &str = "\
+usage: squares-keygen [--count N] [--start-index I] [--format hex|dec] [--distinct]
+       squares-keygen --verify <hex>";
+
+enum Format { Hex, Dec }
+
+fn parse_hex(s: &str) -> Result<u64, String> {
+  let digits = s.trim_start_matches("0x").trim_start_matches("0X");
+  u64::from_str_radix(digits, 16).map_err(|e| format!("invalid hex key {:?}: {}", s, e))
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+  match s {
+    "hex" => Ok(Format::Hex),
+    "dec" => Ok(Format::Dec),
+    _ => Err(format!("unknown format {:?} (expected hex or dec)", s)),
+  }
+}
+
+fn run() -> Result<ExitCode, String> {
+  let mut args = pico_args::Arguments::from_env();
+  if args.contains(["-h", "--help"]) {
+    println!("{}", USAGE);
+    return Ok(ExitCode::SUCCESS);
+  }
+
+  let verify: Option<u64> = args.opt_value_from_fn("--verify", parse_hex).map_err(|e| e.to_string())?;
+  let count: usize = args.opt_value_from_str("--count").map_err(|e| e.to_string())?.unwrap_or(1);
+  let start: u64 = args.opt_value_from_str("--start-index").map_err(|e| e.to_string())?.unwrap_or(0);
+  let format = args.opt_value_from_fn("--format", parse_format).map_err(|e| e.to_string())?.unwrap_or(Format::Hex);
+  let distinct = args.contains("--distinct");
+
+  let rest = args.finish();
+  if !rest.is_empty() {
+    return Err(format!("unexpected arguments: {:?}", rest));
+  }
+
+  if let Some(raw) = verify {
+    return Ok(match Key::checked(raw) {
+      Ok(_) => {
+        println!("{:016x}: admissible", raw);
+        ExitCode::SUCCESS
+      }
+      Err(e) => {
+        eprintln!("{:016x}: inadmissible: {} ({:?})", raw, e.message(), e);
+        ExitCode::from(1)
+      }
+    });
+  }
+
+  let keys: Vec<Key> = if distinct {
+    let mut keys = vec![Key::unchecked(0); count];
+    key_set(start, &mut keys);
+    keys
+  } else {
+    (0..count as u64).map(|i| key(start.wrapping_add(i))).collect()
+  };
+
+  for k in keys {
+    match format {
+      Format::Hex => println!("{:016x}", k.inner()),
+      Format::Dec => println!("{}", k.inner()),
+    }
+  }
+
+  Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+  run().unwrap_or_else(|e| {
+    eprintln!("error: {}\n{}", e, USAGE);
+    ExitCode::from(2)
+  })
+}
@@ -0,0 +1,187 @@
+use crate::{dist::normal_from, f64, f64_open, squares::derive, Key};
+
+/// A standard normal at `index`: Box–Muller with radius from [`f64_open`] at `index`
+/// and angle from [`f64()`] at `derive(index, 1)`, the same pair [`correlated_pair`](crate::correlated_pair) draws.
+fn standard_normal(key: Key, index: u64) -> f64 {
+  normal_from(f64_open(key, index), f64(key, derive(index, 1)))
+}
+
+/// A Wiener process sampled every `dt`, starting from `0` at time `0`: yields the positions
+/// `W(dt), W(2 dt), ...`, forever.
+///
+/// Step `s` adds `sqrt(dt) · Z` for `Z` the standard normal at counter `start_index + s`
+/// (Box–Muller from `index` and `derive(index, 1)`), so any step can be recomputed on its own,
+/// e.g. by [`BrownianPath::increment`].
+#[derive(Clone, Debug)]
+pub struct BrownianPath {
+  key: Key,
+  scale: f64,
+  index: u64,
+  position: f64,
+}
+
+impl BrownianPath {
+  /// ## Panics
+  /// If `dt` is not positive and finite.
+  #[must_use]
+  pub fn new(key: Key, dt: f64, start_index: u64) -> Self {
+    assert!(dt > 0.0 && dt.is_finite(), "BrownianPath: dt must be positive and finite");
+    Self { key, scale: libm::sqrt(dt), index: start_index, position: 0.0 }
+  }
+
+  /// The increment the next step will add, without taking it
+  #[must_use]
+  pub fn increment(&self) -> f64 {
+    self.scale * standard_normal(self.key, self.index)
+  }
+
+  /// The current position, `0` before the first step
+  #[must_use]
+  pub fn position(&self) -> f64 { self.position }
+}
+
+impl Iterator for BrownianPath {
+  type Item = f64;
+
+  #[inline]
+  fn next(&mut self) -> Option<f64> {
+    self.position += self.increment();
+    self.index = self.index.wrapping_add(1);
+    Some(self.position)
+  }
+}
+
+/// Samples a Wiener process at time `t` given `W(t0) = x0` and `W(t1) = x1`, for `t0 <= t <= t1`:
+/// normal with mean `x0 + (x1 - x0) (t - t0) / (t1 - t0)` and variance `(t - t0) (t1 - t) / (t1 - t0)`,
+/// from the standard normal at `index` (as [`BrownianPath`] draws them).
+///
+/// To add resolution to a path without changing its existing points, give every new point its own
+/// index, e.g. [`bridge_index`] of its position in a dyadic refinement, and condition it on the
+/// neighbouring points already fixed. Then each point depends only on its neighbours and its index,
+/// so refining in any order gives the same path.
+///
+/// ## Panics
+/// If `t` is not in `t0..=t1`, or `t0 == t1` with `x0 != x1`.
+#[must_use]
+pub fn brownian_bridge(key: Key, index: u64, t0: f64, t1: f64, x0: f64, x1: f64, t: f64) -> f64 {
+  assert!(t0 <= t && t <= t1, "brownian_bridge: t must lie in t0..=t1");
+  if t0 == t1 {
+    assert!(x0 == x1, "brownian_bridge: distinct values at the same time");
+    return x0;
+  }
+  let span = t1 - t0;
+  let mean = x0 + (x1 - x0) * (t - t0) / span;
+  let var = (t - t0) * (t1 - t) / span;
+  mean + libm::sqrt(var) * standard_normal(key, index)
+}
+
+/// The index for the point `position / 2^level` of a dyadic refinement of a unit interval, with
+/// `position` odd and `level >= 1`: `derive(base, 2^(level - 1) + (position - 1) / 2)`, numbering the
+/// new points of each level after all coarser ones, so no two points share an index.
+///
+/// ## Panics
+/// If `level` is `0` or above `63`, or `position` is even or not below `2^level`.
+#[must_use]
+pub const fn bridge_index(base: u64, level: u32, position: u64) -> u64 {
+  assert!(level >= 1 && level <= 63, "bridge_index: level must be in 1..=63");
+  assert!(position % 2 == 1 && position < 1 << level, "bridge_index: position must be odd and below 2^level");
+  derive(base, (1 << (level - 1)) + (position - 1) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::BTreeMap, vec::Vec};
+  use crate::key;
+  use super::{bridge_index, brownian_bridge, BrownianPath};
+
+  fn variance(xs: &[f64]) -> f64 {
+    let mean = xs.iter().sum::<f64>() / xs.len() as f64;
+    xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / xs.len() as f64
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn increments_scale_with_dt() {
+    for dt in [0.01, 1.0, 4.0] {
+      let path = BrownianPath::new(key(39), dt, 100);
+      let positions: Vec<f64> = path.take(20_000).collect();
+      let steps: Vec<f64> = core::iter::once(positions[0]).chain(positions.windows(2).map(|w| w[1] - w[0])).collect();
+      // the sample variance of 20k normals is within 5% with overwhelming probability
+      assert!((variance(&steps) / dt - 1.0).abs() < 0.05, "{} {}", dt, variance(&steps));
+    }
+
+    let mut path = BrownianPath::new(key(39), 0.5, 7);
+    let step = path.increment();
+    assert_eq!(path.position(), 0.0);
+    assert_eq!(path.next(), Some(step));
+    assert_eq!(BrownianPath::new(key(39), 0.5, 8).increment(), path.increment());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn bridge_moments() {
+    let mids: Vec<f64> = (0..20_000).map(|i| brownian_bridge(key(40), i, 0.0, 1.0, 0.0, 2.0, 0.5)).collect();
+    let mean = mids.iter().sum::<f64>() / mids.len() as f64;
+    assert!((mean - 1.0).abs() < 0.02, "{}", mean);
+    assert!((variance(&mids) - 0.25).abs() < 0.0125, "{}", variance(&mids));
+
+    assert_eq!(brownian_bridge(key(40), 0, 0.0, 1.0, 0.0, 2.0, 1.0), 2.0);
+    assert_eq!(brownian_bridge(key(40), 0, 3.0, 3.0, 1.5, 1.5, 3.0), 1.5);
+  }
+
+  /// refines a path pinned at `W(0) = 0` and `W(1) = end` to `levels`, visiting new points in `order`
+  fn refine(end: f64, levels: u32, order: impl Fn(u32) -> Vec<u64>) -> BTreeMap<u64, f64> {
+    let denom = 1u64 << levels;
+    let mut points = BTreeMap::from([(0, 0.0), (denom, end)]);
+    for level in 1..=levels {
+      let step = denom >> level;
+      for p in order(level) {
+        let at = p * step;
+        let (l, r) = (at - step, at + step);
+        let (xl, xr) = (points[&l], points[&r]);
+        let t = |i: u64| i as f64 / denom as f64;
+        let x = brownian_bridge(key(41), bridge_index(3, level, p), t(l), t(r), xl, xr, t(at));
+        points.insert(at, x);
+      }
+    }
+    points
+  }
+
+  #[test]
+  fn bridge_refinement_order_independent() {
+    let end = BrownianPath::new(key(41), 1.0, 0).next().unwrap();
+    let forward = refine(end, 6, |level| (1..1 << level).step_by(2).collect());
+    let backward = refine(end, 6, |level| { let mut v: Vec<u64> = (1..1 << level).step_by(2).collect(); v.reverse(); v });
+    assert_eq!(forward.len(), 65);
+    assert_eq!(forward, backward);
+
+    // depth first, finishing each half before starting the other
+    fn split(points: &mut BTreeMap<u64, f64>, l: u64, r: u64, denom: u64) {
+      if r - l < 2 { return; }
+      let at = (l + r) / 2;
+      let (level, p) = (denom.trailing_zeros() - (r - l).trailing_zeros() + 1, at / ((r - l) / 2));
+      let t = |i: u64| i as f64 / denom as f64;
+      let x = brownian_bridge(key(41), bridge_index(3, level, p), t(l), t(r), points[&l], points[&r], t(at));
+      points.insert(at, x);
+      split(points, at, r, denom);
+      split(points, l, at, denom);
+    }
+    let mut depth_first = BTreeMap::from([(0, 0.0), (64, end)]);
+    split(&mut depth_first, 0, 64, 64);
+    assert_eq!(depth_first, forward);
+
+    // a coarser refinement is a subset of the finer one
+    let coarse = refine(end, 3, |level| (1..1 << level).step_by(2).collect());
+    for (at, x) in coarse {
+      assert_eq!(forward[&(at << 3)], x);
+    }
+
+    // indices are distinct across levels
+    let mut seen = std::collections::HashSet::new();
+    for level in 1..8 {
+      for p in (1..1u64 << level).step_by(2) {
+        assert!(seen.insert(bridge_index(3, level, p)));
+      }
+    }
+  }
+}
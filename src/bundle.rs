@@ -0,0 +1,168 @@
+use crate::{keys_distinct, u64, Key};
+
+/// Four keys satisfying the inter-key rule (rule 5 of [`Key`]) pairwise, evaluated together at
+/// each counter to produce 256-bit blocks; see [`block256`].
+#[derive(Clone, Copy, Debug)]
+pub struct KeyBundle4([Key; 4]);
+
+impl KeyBundle4 {
+  /// Bundles `keys`, or returns the positions of the first pair sharing their lower 9 nibbles.
+  pub const fn new(keys: [Key; 4]) -> Result<Self, (usize, usize)> {
+    let mut i = 0;
+    while i < 4 {
+      let mut j = i + 1;
+      while j < 4 {
+        if !keys_distinct(keys[i], keys[j]) { return Err((i, j)); }
+        j += 1;
+      }
+      i += 1;
+    }
+    Ok(Self(keys))
+  }
+
+  /// The bundled keys, in lane order
+  #[must_use]
+  pub const fn keys(&self) -> [Key; 4] { self.0 }
+}
+
+/// The 32-byte block at `index`: `u64(keys[lane], index)` little-endian for lanes `0..4`, in order.
+///
+/// The four lanes share nothing but the counter, so they are independent streams
+/// and the optimizer is free to evaluate them side by side.
+#[must_use] #[inline(always)]
+pub const fn block256(bundle: &KeyBundle4, index: u64) -> [u8; 32] {
+  let mut out = [0; 32];
+  let mut lane = 0;
+  while lane < 4 {
+    let word = u64(bundle.0[lane], index).to_le_bytes();
+    let mut b = 0;
+    while b < 8 {
+      out[8 * lane + b] = word[b];
+      b += 1;
+    }
+    lane += 1;
+  }
+  out
+}
+
+/// Fills `out` with the blocks [`block256`]`(bundle, start_index)`, `block256(bundle, start_index + 1)`, ...
+/// A trailing partial block uses the first bytes of the next block.
+/// Consumes `out.len().div_ceil(32)` indices.
+///
+/// Each lane's `counter * key` is stepped by one addition per block rather than a multiply. Built for
+/// x86-64 with AVX-512DQ and AVX-512VL enabled (e.g. `-C target-cpu=native` on a CPU that has them),
+/// the four lanes run in one 256-bit vector through `vpmullq`, and this outpaces [`fill_bytes`](crate::fill_bytes)
+/// (see the `fill_1MiB` bench). Elsewhere the lanes are interleaved scalar code, which is multiply-bound
+/// exactly like `fill_bytes` and no faster.
+#[inline]
+pub fn fill_bytes_wide(bundle: &KeyBundle4, start_index: u64, out: &mut [u8]) {
+  let keys = bundle.0.map(Key::inner);
+  let tail_index = start_index.wrapping_add((out.len() / 32) as u64);
+  let mut chunks = out.chunks_exact_mut(32);
+  let y = core::array::from_fn(|l| start_index.wrapping_mul(keys[l]));
+  kernel::fill_blocks(&keys, y, &mut chunks);
+  let tail = block256(bundle, tail_index);
+  for (dst, src) in chunks.into_remainder().iter_mut().zip(tail) {
+    *dst = src;
+  }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx512dq", target_feature = "avx512vl"))]
+mod kernel {
+  use core::arch::x86_64::*;
+
+  /// Writes each 32-byte chunk from the four lanes at `y`, stepping `y` by `keys` per chunk.
+  #[inline(always)]
+  pub(super) fn fill_blocks(keys: &[u64; 4], y: [u64; 4], chunks: &mut core::slice::ChunksExactMut<'_, u8>) {
+    // SAFETY: the intrinsics' target features are enabled at compile time (the cfg above),
+    // and the loads and stores are unaligned ones on 32 valid bytes each
+    unsafe {
+      let k = _mm256_loadu_si256(keys.as_ptr().cast());
+      let mut y = _mm256_loadu_si256(y.as_ptr().cast());
+      let sq = |x, o| _mm256_add_epi64(_mm256_mullo_epi64(x, x), o);
+      let swap = |x| _mm256_shuffle_epi32::<0b10_11_00_01>(x);
+      for chunk in chunks {
+        let z = _mm256_add_epi64(y, k);
+        let x = swap(sq(y, y));
+        let x = swap(sq(x, z));
+        let x = swap(sq(x, y));
+        let t = sq(x, z);
+        let words = _mm256_xor_si256(t, _mm256_srli_epi64::<32>(sq(swap(t), y)));
+        _mm256_storeu_si256(chunk.as_mut_ptr().cast(), words);
+        y = _mm256_add_epi64(y, k);
+      }
+    }
+  }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "avx512dq", target_feature = "avx512vl")))]
+mod kernel {
+  use crate::primitives::{round, sq, swap};
+
+  /// Writes each 32-byte chunk from the four lanes at `y`, stepping `y` by `keys` per chunk.
+  #[inline(always)]
+  pub(super) fn fill_blocks(keys: &[u64; 4], mut y: [u64; 4], chunks: &mut core::slice::ChunksExactMut<'_, u8>) {
+    for chunk in chunks {
+      let z: [u64; 4] = core::array::from_fn(|l| y[l].wrapping_add(keys[l]));
+      let x: [u64; 4] = core::array::from_fn(|l| round(y[l], y[l]));
+      let x: [u64; 4] = core::array::from_fn(|l| round(x[l], z[l]));
+      let x: [u64; 4] = core::array::from_fn(|l| round(x[l], y[l]));
+      let t: [u64; 4] = core::array::from_fn(|l| sq(x[l], z[l]));
+      let [a, b, c, d] = core::array::from_fn(|l| t[l] ^ (sq(swap(t[l]), y[l]) >> 32)).map(u64::to_le_bytes);
+      chunk[..8].copy_from_slice(&a);
+      chunk[8..16].copy_from_slice(&b);
+      chunk[16..24].copy_from_slice(&c);
+      chunk[24..].copy_from_slice(&d);
+      y = core::array::from_fn(|l| y[l].wrapping_add(keys[l]));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u64, Key};
+  use super::{block256, fill_bytes_wide, KeyBundle4};
+
+  fn bundle() -> KeyBundle4 {
+    KeyBundle4::new([key(0), key(1), key(2), key(3)]).unwrap()
+  }
+
+  #[test]
+  fn block_is_lanes_concatenated() {
+    let b = bundle();
+    for i in [0, 1, u64::MAX] {
+      let block = block256(&b, i);
+      for (lane, k) in b.keys().into_iter().enumerate() {
+        assert_eq!(block[8 * lane..8 * lane + 8], u64(k, i).to_le_bytes());
+      }
+    }
+
+    let mut out = [0; 100];
+    fill_bytes_wide(&b, u64::MAX - 1, &mut out);
+    assert_eq!(out[..32], block256(&b, u64::MAX - 1));
+    assert_eq!(out[32..64], block256(&b, u64::MAX));
+    assert_eq!(out[64..96], block256(&b, 0));
+    assert_eq!(out[96..], block256(&b, 1)[..4]);
+  }
+
+  #[test]
+  fn fill_bytes_wide_matches_blocks() {
+    let b = KeyBundle4::new([key(7), key(8), key(9), Key::unchecked(0xaf9ed4c87b8e4fa5)]).unwrap();
+    for start in [0, 12_345, u64::MAX - 20] {
+      let mut out = [0; 32 * 37 + 19];
+      fill_bytes_wide(&b, start, &mut out);
+      for (i, chunk) in out.chunks(32).enumerate() {
+        assert_eq!(chunk, &block256(&b, start.wrapping_add(i as u64))[..chunk.len()], "start {} block {}", start, i);
+      }
+    }
+  }
+
+  #[test]
+  fn bundle_rejects_colliding_keys() {
+    let clash = Key::unchecked(0x1234_567F_EDCB_A987);
+    let twin = Key::unchecked(0x7654_321F_EDCB_A987);
+    assert_eq!(KeyBundle4::new([key(0), clash, key(1), twin]).unwrap_err(), (1, 3));
+    assert_eq!(KeyBundle4::new([key(0), key(0), key(1), key(2)]).unwrap_err(), (0, 1));
+    assert!(KeyBundle4::new([key(0), key(1), key(2), key(3)]).is_ok());
+  }
+}
@@ -0,0 +1,118 @@
+use crate::{u64, Key};
+
+/// the golden angle, `360° (1 - 1/φ)`, in turns
+const GOLDEN_TURN: f64 = 0.381_966_011_250_105_1;
+
+/// OKLCH lightness range of palette colors
+const LIGHTNESS: (f64, f64) = (0.62, 0.78);
+/// OKLCH chroma range of palette colors, before gamut clipping
+const CHROMA: (f64, f64) = (0.10, 0.15);
+/// half-width of the keyed hue jitter, in turns (2.5°)
+const HUE_JITTER: f64 = 2.5 / 360.0;
+
+/// Fills `out[..n]` with `n` visually distinct sRGB colors, e.g. chart series, fixed by `(key, index)`.
+///
+/// Colors are chosen in OKLCH (Ottosson's OKLab in polar form), where equal steps look roughly equal.
+/// Color `i` takes the draw `d = u64(key, index + 1 + i)`, and `u64(key, index)` picks a base hue `h0`:
+/// - hue `h0 + i · 137.508°` (the golden angle, which keeps any prefix of the palette spread out),
+///   jittered by up to ±2.5° from bits `0..16` of `d`;
+/// - lightness `L` in `0.62..0.78` from bits `16..32`, chroma `C` in `0.10..0.15` from bits `32..48`.
+///
+/// `(L, C, h)` becomes OKLab `(L, C cos h, C sin h)`, then linear sRGB through Ottosson's two matrices
+/// and the cube in between, then the sRGB transfer curve, rounded to `u8`. A color outside the sRGB gamut
+/// has its chroma scaled down until it fits, which keeps its hue. So `n + 1` counters are consumed.
+///
+/// ## Panics
+/// If `n > out.len()`.
+pub fn palette(key: Key, index: u64, n: usize, out: &mut [[u8; 3]]) {
+  assert!(n <= out.len(), "palette: n exceeds the buffer");
+  let h0 = unit(u64(key, index));
+
+  for (i, color) in out[..n].iter_mut().enumerate() {
+    let d = u64(key, index.wrapping_add(1 + i as u64));
+    let field = |shift: u32| unit(d << (48 - shift));
+    let hue = h0 + i as f64 * GOLDEN_TURN + (2.0 * field(0) - 1.0) * HUE_JITTER;
+    let l = LIGHTNESS.0 + (LIGHTNESS.1 - LIGHTNESS.0) * field(16);
+    let c = CHROMA.0 + (CHROMA.1 - CHROMA.0) * field(32);
+    *color = oklch_to_srgb(l, c, hue);
+  }
+}
+
+/// The top 16 bits of `bits` as a fraction in `[0, 1)`
+fn unit(bits: u64) -> f64 { (bits >> 48) as f64 / 65_536.0 }
+
+/// OKLCH (hue in turns) to sRGB, reducing chroma until the color is inside the gamut.
+fn oklch_to_srgb(l: f64, mut c: f64, hue: f64) -> [u8; 3] {
+  let (sin, cos) = libm::sincos(core::f64::consts::TAU * hue);
+  loop {
+    let rgb = oklab_to_linear_srgb(l, c * cos, c * sin);
+    // chroma 0 is grey, always in gamut
+    if rgb.iter().all(|v| (-1e-9..=1.0 + 1e-9).contains(v)) || c <= 0.0 {
+      return rgb.map(|v| libm::round(encode_srgb(v.clamp(0.0, 1.0)) * 255.0) as u8);
+    }
+    c = (c - 0.005).max(0.0);
+  }
+}
+
+/// OKLab to linear sRGB (Ottosson, 2020)
+fn oklab_to_linear_srgb(l: f64, a: f64, b: f64) -> [f64; 3] {
+  let l_ = l + 0.396_337_777_4 * a + 0.215_803_757_3 * b;
+  let m_ = l - 0.105_561_345_8 * a - 0.063_854_172_8 * b;
+  let s_ = l - 0.089_484_177_5 * a - 1.291_485_548_0 * b;
+  let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+  [
+    4.076_741_662_1 * l3 - 3.307_711_591_3 * m3 + 0.230_969_929_2 * s3,
+    -1.268_438_004_6 * l3 + 2.609_757_401_1 * m3 - 0.341_319_396_5 * s3,
+    -0.004_196_086_3 * l3 - 0.703_418_614_7 * m3 + 1.707_614_701_0 * s3,
+  ]
+}
+
+/// the sRGB transfer curve, linear light to encoded
+fn encode_srgb(v: f64) -> f64 {
+  if v <= 0.003_130_8 { 12.92 * v } else { 1.055 * libm::pow(v, 1.0 / 2.4) - 0.055 }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use super::palette;
+
+  /// sRGB back to OKLCH hue, in degrees
+  fn hue(rgb: [u8; 3]) -> f64 {
+    let decode = |v: u8| {
+      let v = v as f64 / 255.0;
+      if v <= 0.040_45 { v / 12.92 } else { libm::pow((v + 0.055) / 1.055, 2.4) }
+    };
+    let [r, g, b] = rgb.map(decode);
+    let l = libm::cbrt(0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b);
+    let m = libm::cbrt(0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b);
+    let s = libm::cbrt(0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b);
+    let a = 1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s;
+    let b = 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s;
+    libm::atan2(b, a).to_degrees().rem_euclid(360.0)
+  }
+
+  #[test]
+  fn palette_hues_separated() {
+    for index in 0..20 {
+      for n in [1, 2, 5, 12, 20] {
+        let mut out = [[0; 3]; 20];
+        palette(key(33), index, n, &mut out);
+        assert!(out[n..].iter().all(|&c| c == [0; 3]));
+
+        let hues: std::vec::Vec<f64> = out[..n].iter().map(|&c| hue(c)).collect();
+        for (i, a) in hues.iter().enumerate() {
+          for b in &hues[i + 1..] {
+            let d = (a - b).abs();
+            assert!(d.min(360.0 - d) > 5.0, "{} {} {:?}", index, n, hues);
+          }
+        }
+        // readable: neither near black nor near white
+        for c in &out[..n] {
+          let brightest = *c.iter().max().unwrap();
+          assert!((100..=255).contains(&brightest) && c.iter().any(|&v| v < 235), "{:?}", c);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,49 @@
+use crate::{u64, Key};
+
+/// Prints `(index, u64(key, index))` for `n` consecutive indices to stderr.
+/// A development aid for eyeballing a stream.
+pub fn debug_stream(key: Key, start_index: u64, n: usize) {
+  for i in 0..n as u64 {
+    let index = start_index.wrapping_add(i);
+    std::eprintln!("({}, {:#018x})", index, u64(key, index));
+  }
+}
+
+/// Times `iterations` calls of [`u64()`] under `key` and returns the rate in values per second,
+/// for self-benchmarking on the target hardware. Returns `0` for `0` iterations.
+///
+/// Build with optimizations for meaningful numbers; small counts are dominated by timer resolution.
+pub fn measure_throughput(key: Key, iterations: u64) -> u64 {
+  if iterations == 0 { return 0; }
+
+  let start = std::time::Instant::now();
+  let mut acc = 0u64;
+  for i in 0..iterations {
+    acc ^= u64(core::hint::black_box(key), i);
+  }
+  core::hint::black_box(acc);
+  let nanos = start.elapsed().as_nanos().max(1);
+
+  (iterations as u128 * 1_000_000_000 / nanos).min(u64::MAX as u128).max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use super::{debug_stream, measure_throughput};
+
+  #[test]
+  fn debug_stream_runs() {
+    debug_stream(key(1), u64::MAX - 2, 5);
+    debug_stream(key(1), 0, 0);
+  }
+
+  #[test]
+  fn measure_throughput_positive() {
+    let start = std::time::Instant::now();
+    assert!(measure_throughput(key(1), 1_000) > 0);
+    assert!(measure_throughput(key(1), 1) > 0);
+    assert_eq!(measure_throughput(key(1), 0), 0);
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+  }
+}
@@ -0,0 +1,499 @@
+use crate::{derive, f64, f64_open, retry_until, unit_f64, Key};
+
+/// Fills `out` with a uniformly random point on the probability simplex:
+/// every component is non-negative and they sum to one.
+///
+/// Sorts `out.len() - 1` uniforms (indices `start_index..`) and takes the gaps between them.
+///
+/// ## Panics
+/// If `out.len() < 2`.
+pub fn simplex_point(key: Key, start_index: u64, out: &mut [f64]) {
+  let n = out.len();
+  assert!(n >= 2, "simplex_point: out.len() must be at least 2");
+
+  for (i, x) in out[..n - 1].iter_mut().enumerate() {
+    *x = f64(key, start_index.wrapping_add(i as u64));
+  }
+  out[..n - 1].sort_unstable_by(f64::total_cmp);
+  out[n - 1] = 1.0;
+
+  for i in (1..n).rev() {
+    out[i] -= out[i - 1];
+  }
+}
+
+/// Draws a uniformly distributed point inside the disk of `radius` centred on the origin:
+/// radius `radius * sqrt(u)` (so area, not distance, is uniform) with `u` = [`f64()`] at `start_index`,
+/// and angle `2π v` with `v` at `start_index + 1`.
+///
+/// ## Panics
+/// If `radius` is negative or not finite.
+#[must_use]
+pub fn point_in_disk(key: Key, start_index: u64, radius: f64) -> (f64, f64) {
+  assert!(radius >= 0.0 && radius.is_finite(), "point_in_disk: radius must be non-negative and finite");
+  let r = radius * libm::sqrt(f64(key, start_index));
+  let (sin, cos) = libm::sincos(core::f64::consts::TAU * f64(key, start_index.wrapping_add(1)));
+  (r * cos, r * sin)
+}
+
+/// Draws an exponentially distributed value with rate `rate` (mean `1 / rate`),
+/// by inverse transform of [`f64_open`] at `index`.
+///
+/// ## Panics
+/// If `rate` is not positive and finite.
+#[must_use]
+pub fn exponential(key: Key, index: u64, rate: f64) -> f64 {
+  assert!(rate > 0.0 && rate.is_finite(), "exponential: rate must be positive and finite");
+  -libm::log(f64_open(key, index)) / rate
+}
+
+/// Draws a Weibull distributed value, `scale * (-ln u)^(1 / shape)`, by inverse transform of [`f64_open`] at `index`.
+/// `shape = 1` is the exponential distribution with rate `1 / scale`.
+///
+/// For `shape < 1` the density is unbounded at zero, so very small values are common
+/// (at `shape = 0.5`, a tenth of draws are below `0.011 * scale`).
+///
+/// ## Panics
+/// If `shape` or `scale` is not positive and finite.
+#[must_use]
+pub fn weibull(key: Key, index: u64, shape: f64, scale: f64) -> f64 {
+  assert!(shape > 0.0 && shape.is_finite(), "weibull: shape must be positive and finite");
+  assert!(scale > 0.0 && scale.is_finite(), "weibull: scale must be positive and finite");
+  scale * libm::pow(-libm::log(f64_open(key, index)), 1.0 / shape)
+}
+
+/// A standard normal from two uniforms (Box–Muller), `u1` in `(0, 1)`.
+pub(crate) fn normal_from(u1: f64, u2: f64) -> f64 {
+  libm::sqrt(-2.0 * libm::log(u1)) * libm::cos(core::f64::consts::TAU * u2)
+}
+
+/// the standard normal CDF, `Φ(x) = erfc(-x / √2) / 2`, through `libm`'s `erfc` (accurate to ~1 ulp, and `no_std`),
+/// clamped into the open interval `(0, 1)`
+fn normal_cdf_open(x: f64) -> f64 {
+  let p = 0.5 * libm::erfc(-x * core::f64::consts::FRAC_1_SQRT_2);
+  p.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON / 2.0)
+}
+
+/// Draws a pair of uniforms in `(0, 1)` with rank (Spearman) correlation `rho`, joined by a Gaussian copula:
+/// two standard normals (Box–Muller, radius from [`f64_open`] at `index`, angle from [`f64()`] at `derive(index, 1)`, see [`beta`])
+/// are mixed by the Cholesky factor of `[[1, rho_n], [rho_n, 1]]` and mapped back through `Φ`.
+///
+/// The normals' correlation is `rho_n = 2 sin(π rho / 6)`, the inverse of the Gaussian copula's
+/// `rho = (6 / π) asin(rho_n / 2)`, so the uniforms' rank correlation is `rho` itself.
+/// The first value alone is uniform at `index`.
+///
+/// ## Panics
+/// If `rho` is not in `[-1, 1]`.
+#[must_use]
+pub fn correlated_pair(key: Key, index: u64, rho: f64) -> (f64, f64) {
+  assert!((-1.0..=1.0).contains(&rho), "correlated_pair: rho must be in [-1, 1]");
+
+  let r = libm::sqrt(-2.0 * libm::log(f64_open(key, index)));
+  let (sin, cos) = libm::sincos(core::f64::consts::TAU * f64(key, derive(index, 1)));
+  let (z1, z2) = (r * cos, r * sin);
+
+  // exact at the ends, where the rounded sine falls just short of 1
+  let rho_n = if rho.abs() == 1.0 { rho } else { 2.0 * libm::sin(core::f64::consts::PI * rho / 6.0) };
+  let x = z1;
+  let y = rho_n * z1 + libm::sqrt(1.0 - rho_n * rho_n) * z2;
+  (normal_cdf_open(x), normal_cdf_open(y))
+}
+
+/// Gamma(`shape`, 1) by Marsaglia–Tsang, drawing from lane `lane` of the sub-counters of `index`:
+/// attempt `t` uses `derive(index, 8t + 4 lane + 1 + j)` for `j` in `0..4`
+/// (`j = 0, 1` the normal, `j = 2` the acceptance test, `j = 3` the `shape < 1` boost).
+fn gamma_lane(key: Key, index: u64, lane: u64, shape: f64) -> f64 {
+  let boosted = if shape < 1.0 { shape + 1.0 } else { shape };
+  let d = boosted - 1.0 / 3.0;
+  let c = 1.0 / libm::sqrt(9.0 * d);
+
+  let mut t: u64 = 0;
+  loop {
+    let k = t.wrapping_mul(8).wrapping_add(4 * lane + 1);
+    let draw = |j: u64| f64_open(key, derive(index, k.wrapping_add(j)));
+    t = t.wrapping_add(1);
+
+    let x = normal_from(draw(0), draw(1));
+    let v = 1.0 + c * x;
+    if v <= 0.0 { continue; }
+    let v = v * v * v;
+    if libm::log(draw(2)) >= 0.5 * x * x + d - d * v + d * libm::log(v) { continue; }
+
+    let g = d * v;
+    return if shape < 1.0 { g * libm::pow(draw(3), 1.0 / shape) } else { g };
+  }
+}
+
+/// Draws a Beta(`alpha`, `beta`) distributed value in `(0, 1)`, with mean `alpha / (alpha + beta)`.
+///
+/// Sub-counter scheme: draw `k` is [`f64_open`] at counter
+/// `u64(Key::unchecked(0xd5c5d87a2e576b81), index).wrapping_add(k)`, written `derive(index, k)`:
+/// - if `alpha <= 1` and `beta <= 1`, Jöhnk's method: attempt `t` uses `k = 2t + 1` and `2t + 2`;
+/// - otherwise `X / (X + Y)` for `X ~ Gamma(alpha)` and `Y ~ Gamma(beta)` (Marsaglia–Tsang),
+///   where attempt `t` of `X` uses `k = 8t + 1 ..= 8t + 4` and of `Y` uses `k = 8t + 5 ..= 8t + 8`.
+///
+/// Results that would round to exactly `0` or `1` (possible for extreme parameters) are
+/// nudged to the nearest value inside the interval.
+///
+/// ## Panics
+/// If `alpha` or `beta` is not positive and finite.
+#[must_use]
+pub fn beta(key: Key, index: u64, alpha: f64, beta: f64) -> f64 {
+  assert!(alpha > 0.0 && alpha.is_finite(), "beta: alpha must be positive and finite");
+  assert!(beta > 0.0 && beta.is_finite(), "beta: beta must be positive and finite");
+
+  let x = if alpha <= 1.0 && beta <= 1.0 {
+    johnk(key, index, alpha, beta)
+  } else {
+    let x = gamma_lane(key, index, 0, alpha);
+    let y = gamma_lane(key, index, 1, beta);
+    x / (x + y)
+  };
+
+  // NaN (both gammas underflowed) and 0 go to the smallest positive value
+  if x.is_nan() || x <= 0.0 { return f64::MIN_POSITIVE; }
+  if x >= 1.0 { return 1.0 - f64::EPSILON / 2.0; }
+  x
+}
+
+/// Jöhnk's method for Beta with both parameters at most 1, in log space so tiny parameters don't underflow.
+fn johnk(key: Key, index: u64, alpha: f64, beta: f64) -> f64 {
+  let mut t: u64 = 0;
+  loop {
+    let k = t.wrapping_mul(2).wrapping_add(1);
+    t = t.wrapping_add(1);
+
+    let lx = libm::log(f64_open(key, derive(index, k))) / alpha;
+    let ly = libm::log(f64_open(key, derive(index, k.wrapping_add(1)))) / beta;
+    let lm = lx.max(ly);
+    let (ex, ey) = (libm::exp(lx - lm), libm::exp(ly - lm));
+
+    // accept when X + Y <= 1, where X = e^lx and Y = e^ly
+    if lm + libm::log(ex + ey) <= 0.0 {
+      return ex / (ex + ey);
+    }
+  }
+}
+
+/// `ln(x!)`
+fn ln_factorial(x: f64) -> f64 {
+  libm::lgamma(x + 1.0)
+}
+
+/// Draws the number of successes among `draws` items taken without replacement from a `population`
+/// containing `successes` successes, e.g. defective parts found when inspecting a sample of a lot.
+///
+/// Symmetries reduce every case to one with at most as many successes as failures and at most
+/// half the population drawn. Then, if the mode `M` is below 10, the CDF is walked from 0 with draw `k`
+/// of attempt `k` (HIN); otherwise Kachitvichyanukul and Schmeiser's H2PE rejection is used,
+/// with attempt `t` taking draws `2t` and `2t + 1`. Draw `k` is at counter `derive(index, k)` (see [`beta`]).
+///
+/// Probabilities are evaluated in `f64`, so parameters beyond `2^53` lose precision.
+///
+/// ## Panics
+/// If `successes > population` or `draws > population`.
+#[must_use]
+pub fn hypergeometric(key: Key, index: u64, population: u64, successes: u64, draws: u64) -> u64 {
+  assert!(successes <= population, "hypergeometric: successes must not exceed population");
+  assert!(draws <= population, "hypergeometric: draws must not exceed population");
+
+  // count whichever kind is rarer, among whichever of the drawn or undrawn items is fewer
+  let swap_kinds = successes > population - successes;
+  let n1 = if swap_kinds { population - successes } else { successes };
+  let swap_draws = draws > population / 2;
+  let k = if swap_draws { population - draws } else { draws };
+
+  let x = hypergeometric_reduced(key, index, n1, population - n1, k);
+  let x = if swap_draws { n1 - x } else { x };
+  if swap_kinds { draws - x } else { x }
+}
+
+/// [`hypergeometric`] with `n1 <= n2` kinds and `k <= (n1 + n2) / 2` draws, whose support is `0..=min(n1, k)`.
+fn hypergeometric_reduced(key: Key, index: u64, n1: u64, n2: u64, k: u64) -> u64 {
+  let max = n1.min(k);
+  if max == 0 { return 0; }
+
+  let (n1f, n2f, kf) = (n1 as f64, n2 as f64, k as f64);
+  let nf = n1f + n2f;
+  let mode = libm::floor((kf + 1.0) * (n1f + 1.0) / (nf + 2.0));
+
+  if mode < 10.0 {
+    // HIN: f(0) = C(n2, k) / C(N, k), then f(x + 1) / f(x) = (n1 - x)(k - x) / ((x + 1)(n2 - k + x + 1))
+    let f0 = libm::exp(ln_factorial(n2f) + ln_factorial(nf - kf) - ln_factorial(n2f - kf) - ln_factorial(nf));
+    let walk = |draw: u64| {
+      let mut u = unit_f64(draw);
+      let mut p = f0;
+      let mut x = 0;
+      while u > p && x < max {
+        u -= p;
+        let xf = x as f64;
+        p *= (n1f - xf) * (kf - xf) / ((xf + 1.0) * (n2f - kf + xf + 1.0));
+        x += 1;
+      }
+      // running off the end only happens through rounding; redraw
+      (u <= p).then_some(x)
+    };
+    // 2^32 rounding failures in a row do not happen; `max` just keeps this total
+    return retry_until(key, index, u32::MAX, walk).map_or(max, |(x, _)| x);
+  }
+
+  // H2PE: a rectangle around the mode with exponential tails, f(y) / f(M) = exp(a - g(y))
+  let g = |x: f64| ln_factorial(x) + ln_factorial(n1f - x) + ln_factorial(kf - x) + ln_factorial(n2f - kf + x);
+  let a = g(mode);
+  let var = kf * (nf - kf) * n1f * n2f / (nf * nf * (nf - 1.0));
+  let d = libm::floor(1.5 * libm::sqrt(var)) + 0.5;
+  let x_l = mode - d + 0.5;
+  let x_r = mode + d + 0.5;
+  let k_l = libm::exp(a - g(x_l));
+  let k_r = libm::exp(a - g(x_r - 1.0));
+  let lambda_l = -libm::log(x_l * (n2f - kf + x_l) / ((n1f - x_l + 1.0) * (kf - x_l + 1.0)));
+  let lambda_r = -libm::log((n1f - x_r + 1.0) * (kf - x_r + 1.0) / (x_r * (n2f - kf + x_r)));
+  let p1 = 2.0 * d;
+  let p2 = p1 + k_l / lambda_l;
+  let p3 = p2 + k_r / lambda_r;
+
+  let mut t: u64 = 0;
+  loop {
+    let u = f64(key, derive(index, t.wrapping_mul(2))) * p3;
+    let mut v = f64_open(key, derive(index, t.wrapping_mul(2).wrapping_add(1)));
+    t = t.wrapping_add(1);
+
+    let y = if u < p1 {
+      libm::floor(x_l + u)
+    } else if u < p2 {
+      let y = libm::floor(x_l + libm::log(v) / lambda_l);
+      if y < 0.0 { continue; }
+      v *= (u - p1) * lambda_l;
+      y
+    } else {
+      let y = libm::floor(x_r - libm::log(v) / lambda_r);
+      if y > max as f64 { continue; }
+      v *= (u - p2) * lambda_r;
+      y
+    };
+
+    if libm::log(v) <= a - g(y) { return y as u64; }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use super::{beta, correlated_pair, exponential, hypergeometric, ln_factorial, point_in_disk, simplex_point, weibull};
+
+  #[test]
+  fn simplex_point_on_simplex() {
+    let k = key(8);
+    let mut means = [0.0; 5];
+
+    for s in 0..2_000 {
+      let mut p = [0.0; 5];
+      simplex_point(k, s * 4, &mut p);
+      assert!(p.iter().all(|&x| x >= 0.0), "{:?}", p);
+      assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12, "{:?}", p);
+      for (m, x) in means.iter_mut().zip(p) {
+        *m += x / 2_000.0;
+      }
+    }
+
+    // all components are exchangeable, so each averages 1/n
+    for m in means {
+      assert!((m - 0.2).abs() < 0.02, "{:?}", means);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn point_in_disk_uniform_in_area() {
+    let k = key(10);
+    let radius = 3.0;
+    let n = 40_000;
+    // equal-area rings, and quadrants
+    let mut rings = [0u32; 10];
+    let mut quadrants = [0u32; 4];
+
+    for s in 0..n {
+      let (x, y) = point_in_disk(k, 2 * s, radius);
+      let r2 = x * x + y * y;
+      assert!(r2 <= radius * radius, "{} {}", x, y);
+      rings[((r2 / (radius * radius)) * 10.0) as usize] += 1;
+      quadrants[(x < 0.0) as usize * 2 + (y < 0.0) as usize] += 1;
+    }
+
+    for c in rings { assert!((c as i32 - 4_000).abs() < 300, "{:?}", rings); }
+    for c in quadrants { assert!((c as i32 - 10_000).abs() < 400, "{:?}", quadrants); }
+    assert_eq!(point_in_disk(k, 0, 0.0), (0.0, 0.0));
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn weibull_quantiles() {
+    let k = key(9);
+    let n = 20_000;
+    for shape in [0.5, 1.0, 3.0] {
+      let draws: std::vec::Vec<f64> = (0..n).map(|i| weibull(k, i, shape, 2.0)).collect();
+      assert!(draws.iter().all(|&x| x > 0.0 && x.is_finite()));
+
+      for p in [0.1, 0.5, 0.9] {
+        let q = 2.0 * libm::pow(-libm::log(1.0 - p), 1.0 / shape);
+        let below = draws.iter().filter(|&&x| x < q).count() as f64 / n as f64;
+        // 5 standard errors at n = 20k
+        assert!((below - p).abs() < 0.011, "shape {} p {} got {}", shape, p, below);
+      }
+    }
+  }
+
+  #[test]
+  fn weibull_shape_one_is_exponential() {
+    let k = key(9);
+    for i in 0..1_000 {
+      let (w, e) = (weibull(k, i, 1.0, 0.25), exponential(k, i, 4.0));
+      assert!((w - e).abs() <= 1e-12 * e.max(1.0), "{} {}", w, e);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn beta_moments() {
+    let k = key(10);
+    let n = 20_000;
+    for (a, b) in [(0.5, 0.5), (0.2, 0.9), (1.0, 1.0), (2.0, 5.0), (0.3, 3.0), (10.0, 10.0), (40.0, 2.5)] {
+      let draws: std::vec::Vec<f64> = (0..n).map(|i| beta(k, i, a, b)).collect();
+      assert!(draws.iter().all(|&x| x > 0.0 && x < 1.0), "({}, {})", a, b);
+
+      let mean = draws.iter().sum::<f64>() / n as f64;
+      let var = draws.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+      let (m, v) = (a / (a + b), a * b / ((a + b) * (a + b) * (a + b + 1.0)));
+      // 5 standard errors of the mean; variance within 5%
+      assert!((mean - m).abs() < 5.0 * libm::sqrt(v / n as f64), "({}, {}) mean {} vs {}", a, b, mean, m);
+      assert!((var - v).abs() < 0.05 * v, "({}, {}) var {} vs {}", a, b, var, v);
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn beta_symmetric() {
+    let k = key(10);
+    let n = 20_000;
+    for a in [0.4, 3.0] {
+      let low = (0..n).filter(|&i| beta(k, i, a, a) < 0.25).count() as f64;
+      let high = (0..n).filter(|&i| beta(k, i, a, a) > 0.75).count() as f64;
+      assert!((low - high).abs() < 5.0 * libm::sqrt(low + high), "{} {} {}", a, low, high);
+    }
+  }
+
+  #[test]
+  fn beta_extremes_stay_inside() {
+    for i in 0..200 {
+      for (a, b) in [(1e-3, 1e-3), (1e-3, 50.0), (50.0, 1e-3)] {
+        let x = beta(key(11), i, a, b);
+        assert!(x > 0.0 && x < 1.0, "({}, {}) -> {}", a, b, x);
+      }
+    }
+  }
+
+  /// exact P(X = x) for the hypergeometric distribution
+  fn hypergeometric_pmf(population: u64, successes: u64, draws: u64, x: u64) -> f64 {
+    let ln_choose = |n: u64, k: u64| ln_factorial(n as f64) - ln_factorial(k as f64) - ln_factorial((n - k) as f64);
+    if x > successes || draws - x > population - successes { return 0.0; }
+    libm::exp(ln_choose(successes, x) + ln_choose(population - successes, draws - x) - ln_choose(population, draws))
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn hypergeometric_matches_pmf() {
+    let k = key(13);
+    let n = 40_000;
+    // small modes exercise the CDF walk under every symmetry, the last two H2PE
+    for (pop, succ, draws) in [(10, 4, 5), (12, 9, 3), (12, 3, 11), (20, 15, 14), (500, 480, 300), (200, 80, 60), (1_000, 300, 700)] {
+      let mut counts = std::vec![0u32; draws as usize + 1];
+      for i in 0..n {
+        counts[hypergeometric(k, i, pop, succ, draws) as usize] += 1;
+      }
+      for (x, &c) in counts.iter().enumerate() {
+        let expected = n as f64 * hypergeometric_pmf(pop, succ, draws, x as u64);
+        assert!((c as f64 - expected).abs() < 5.0 * libm::sqrt(expected) + 1.0,
+          "({}, {}, {}) x = {}: {} vs {}", pop, succ, draws, x, c, expected);
+      }
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn hypergeometric_large_moments() {
+    let k = key(13);
+    let n = 20_000;
+    for (pop, succ, draws) in [(1_000_u64, 400, 300), (1_000_000_000_000, 300_000_000_000, 1_000_000_000)] {
+      let (p, nf) = (succ as f64 / pop as f64, draws as f64);
+      let m = nf * p;
+      let v = nf * p * (1.0 - p) * (pop - draws) as f64 / (pop - 1) as f64;
+
+      let draws: std::vec::Vec<f64> = (0..n).map(|i| hypergeometric(k, i, pop, succ, draws) as f64).collect();
+      let mean = draws.iter().sum::<f64>() / n as f64;
+      let var = draws.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+      assert!((mean - m).abs() < 5.0 * libm::sqrt(v / n as f64), "{} vs {}", mean, m);
+      assert!((var - v).abs() < 0.05 * v, "{} vs {}", var, v);
+    }
+  }
+
+  #[test]
+  fn hypergeometric_boundaries() {
+    let k = key(13);
+    for i in 0..100 {
+      assert_eq!(hypergeometric(k, i, 50, 20, 0), 0);
+      assert_eq!(hypergeometric(k, i, 50, 20, 50), 20);
+      assert_eq!(hypergeometric(k, i, 50, 0, 30), 0);
+      assert_eq!(hypergeometric(k, i, 50, 50, 30), 30);
+      assert_eq!(hypergeometric(k, i, 0, 0, 0), 0);
+      assert!(hypergeometric(k, i, 50, 45, 40) >= 35);
+    }
+  }
+
+  /// Spearman rank correlation of paired samples (no ties)
+  fn spearman(pairs: &[(f64, f64)]) -> f64 {
+    let ranks = |xs: std::vec::Vec<f64>| {
+      let mut order: std::vec::Vec<usize> = (0..xs.len()).collect();
+      order.sort_unstable_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+      let mut r = std::vec![0.0; xs.len()];
+      for (rank, i) in order.into_iter().enumerate() { r[i] = rank as f64; }
+      r
+    };
+    let a = ranks(pairs.iter().map(|p| p.0).collect());
+    let b = ranks(pairs.iter().map(|p| p.1).collect());
+    let n = pairs.len() as f64;
+    let d2: f64 = a.iter().zip(&b).map(|(x, y)| (x - y) * (x - y)).sum();
+    1.0 - 6.0 * d2 / (n * (n * n - 1.0))
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn correlated_pair_rank_correlation() {
+    let k = key(15);
+    let n = 20_000;
+    for rho in [-0.8, 0.0, 0.5, 0.95] {
+      let pairs: std::vec::Vec<(f64, f64)> = (0..n).map(|i| correlated_pair(k, i, rho)).collect();
+      assert!(pairs.iter().all(|&(u, v)| u > 0.0 && u < 1.0 && v > 0.0 && v < 1.0));
+
+      let got = spearman(&pairs);
+      assert!((got - rho).abs() < 0.02, "rho {}: {}", rho, got);
+
+      // both marginals uniform: 10 buckets of 2000
+      for marginal in [0, 1] {
+        let mut buckets = [0i64; 10];
+        for p in &pairs {
+          buckets[((if marginal == 0 { p.0 } else { p.1 }) * 10.0) as usize] += 1;
+        }
+        assert!(buckets.iter().all(|&c| (c - 2_000).abs() < 220), "rho {}: {:?}", rho, buckets);
+      }
+    }
+  }
+
+  #[test]
+  fn correlated_pair_rejects_bad_rho() {
+    for rho in [1.5, -1.01, f64::NAN] {
+      assert!(std::panic::catch_unwind(|| correlated_pair(key(15), 0, rho)).is_err(), "{}", rho);
+    }
+    let (u, v) = correlated_pair(key(15), 0, 1.0);
+    assert!((u - v).abs() < 1e-12);
+  }
+}
@@ -0,0 +1,92 @@
+use crate::{u64, Key};
+
+/// the key used to fold seed material into a single index
+const FOLD_KEY: Key = Key::unchecked(0x11bf69ad857b9ef1);
+/// the state a fold starts from
+const FOLD_INIT: u64 = 0x9e3779b97f4a7c15;
+
+/// one compression step: absorbs `word` into `state`
+#[must_use] #[inline(always)]
+pub(crate) const fn fold_step(state: u64, word: u64) -> u64 {
+  u64(FOLD_KEY, state ^ word)
+}
+
+/// Folds `bytes` into a `u64`.
+/// Bytes are absorbed as little-endian `u64` words (the last zero-padded), then the length.
+#[must_use] #[inline]
+pub(crate) const fn fold_bytes(bytes: &[u8]) -> u64 {
+  let mut state = FOLD_INIT;
+  let mut i = 0;
+  while i < bytes.len() {
+    let mut word = 0;
+    let mut j = 0;
+    while j < 8 && i + j < bytes.len() {
+      word |= (bytes[i + j] as u64) << (j * 8);
+      j += 1;
+    }
+    state = fold_step(state, word);
+    i += 8;
+  }
+  fold_step(state, bytes.len() as u64)
+}
+
+/// Folds `words` into a `u64`, for building your own derivations from several values.
+///
+/// Each word is absorbed in order, then the count, through [`u64()`] under a fixed internal key
+/// as the compression function: `state = u64(FOLD_KEY, state ^ word)`. The result is order-dependent
+/// (`[a, b]` and `[b, a]` differ, as do `[a]` and `[a, 0]`) and is the index [`key_from_seeds`](crate::key_from_seeds) uses.
+///
+/// This is a mixer, not a cryptographic hash.
+#[must_use] #[inline]
+pub const fn fold_u64(words: &[u64]) -> u64 {
+  let mut state = FOLD_INIT;
+  let mut i = 0;
+  while i < words.len() {
+    state = fold_step(state, words[i]);
+    i += 1;
+  }
+  fold_step(state, words.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{fold_bytes, fold_u64};
+
+  #[test]
+  fn fold_u64_sensitive() {
+    let base = [1, u64::MAX, 0, 0x0123_4567_89ab_cdef];
+    let h = fold_u64(&base);
+    assert_eq!(h, fold_u64(&base));
+
+    for i in 0..base.len() {
+      for bit in 0..64 {
+        let mut w = base;
+        w[i] ^= 1 << bit;
+        assert_ne!(fold_u64(&w), h, "word {} bit {}", i, bit);
+      }
+    }
+
+    assert_ne!(fold_u64(&[]), fold_u64(&[0]));
+    assert_ne!(fold_u64(&[1, 2]), fold_u64(&[2, 1]));
+  }
+
+  #[test]
+  fn fold_bytes_sensitive() {
+    let base = *b"the quick brown fox";
+    let h = fold_bytes(&base);
+    assert_eq!(h, fold_bytes(&base));
+
+    for i in 0..base.len() {
+      for bit in 0..8 {
+        let mut b = base;
+        b[i] ^= 1 << bit;
+        assert_ne!(fold_bytes(&b), h, "byte {} bit {}", i, bit);
+      }
+    }
+
+    // zero padding and length are distinguished
+    assert_ne!(fold_bytes(&[]), fold_bytes(&[0]));
+    assert_ne!(fold_bytes(&[0]), fold_bytes(&[0, 0]));
+    assert_ne!(fold_bytes(&[0; 8]), fold_bytes(&[0; 9]));
+  }
+}
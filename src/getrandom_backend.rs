@@ -0,0 +1,119 @@
+//! A Squares-backed `getrandom` source for targets without OS entropy (feature `getrandom-backend`).
+//!
+//! Enabling the feature registers [`getrandom_fill`] as getrandom 0.2's custom handler, so it
+//! conflicts with any other `register_custom_getrandom!` in the final binary. getrandom only consults
+//! custom handlers on targets it doesn't natively support (bare-metal, `wasm32-unknown-unknown`);
+//! elsewhere the OS source keeps being used.
+//!
+//! Only 32-bit atomics are used (the key is stored in two halves, and the counter is 32 bits),
+//! through `portable-atomic`, which is native wherever the target has compare-and-swap.
+//! On targets without it, such as `thumbv6m` and `riscv32imc`, enable `portable-atomic`'s
+//! `critical-section` feature (and link a `critical-section` implementation), or build with
+//! `--cfg portable_atomic_unsafe_assume_single_core` on single-core chips.
+
+use portable_atomic::{AtomicU32, AtomicU8, Ordering};
+use crate::{fill_bytes, Key};
+
+const UNSET: u8 = 0;
+const WRITING: u8 = 1;
+const READY: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNSET);
+static KEY_LO: AtomicU32 = AtomicU32::new(0);
+static KEY_HI: AtomicU32 = AtomicU32::new(0);
+/// the next unused word of the stream
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// The `getrandom` error code [`getrandom_fill`] returns once the stream's `2^32` words are used up
+pub const GETRANDOM_EXHAUSTED: u32 = getrandom::Error::CUSTOM_START + 1;
+
+getrandom::register_custom_getrandom!(getrandom_fill);
+
+/// Returned by [`register_getrandom_backend`] when a key was already installed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlreadyRegistered;
+
+/// Installs `key` as the source behind every later `getrandom` call.
+/// Only the first registration takes effect; later ones return [`AlreadyRegistered`] and leave
+/// the stream untouched.
+///
+/// **All of the "entropy" handed out is `key`.** Derive it from a real entropy source
+/// read once at startup (such as a hardware TRNG, e.g. via [`key_from_bytes`](crate::key_from_bytes));
+/// anyone who knows or guesses the key can reproduce every byte, and a fixed key gives every
+/// device the same stream after each reset.
+pub fn register_getrandom_backend(key: Key) -> Result<(), AlreadyRegistered> {
+  STATE.compare_exchange(UNSET, WRITING, Ordering::Acquire, Ordering::Relaxed).map_err(|_| AlreadyRegistered)?;
+  KEY_LO.store(key.inner() as u32, Ordering::Relaxed);
+  KEY_HI.store((key.inner() >> 32) as u32, Ordering::Relaxed);
+  STATE.store(READY, Ordering::Release);
+  Ok(())
+}
+
+/// The registered `getrandom` handler: fills `dest` with the byte stream [`fill_bytes`] writes
+/// at a shared counter, which each call advances by `dest.len().div_ceil(8)` words (so calls never
+/// overlap, and leftover tail bytes are discarded).
+/// The bytes are a deterministic function of the key and the order of calls.
+///
+/// Fails with `getrandom::Error::UNSUPPORTED` before [`register_getrandom_backend`], and with
+/// [`GETRANDOM_EXHAUSTED`] (rather than repeating the stream) once a call would run past
+/// word `2^32` (32 GiB after registration).
+pub fn getrandom_fill(dest: &mut [u8]) -> Result<(), getrandom::Error> {
+  if STATE.load(Ordering::Acquire) != READY { return Err(getrandom::Error::UNSUPPORTED); }
+  let key = Key::unchecked((KEY_HI.load(Ordering::Relaxed) as u64) << 32 | KEY_LO.load(Ordering::Relaxed) as u64);
+
+  let exhausted = || getrandom::Error::from(core::num::NonZeroU32::new(GETRANDOM_EXHAUSTED).unwrap());
+  let words = u32::try_from(dest.len().div_ceil(8)).map_err(|_| exhausted())?;
+  let start = COUNTER
+    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| next.checked_add(words))
+    .map_err(|_| exhausted())?;
+  fill_bytes(key, start as u64, dest);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{fill_bytes, key};
+  use super::{getrandom_fill, register_getrandom_backend, AlreadyRegistered};
+
+  // the handler is global, so everything is exercised from one test
+  #[test]
+  fn registered_handler_is_deterministic() {
+    let mut buf = [0u8; 12];
+    assert_eq!(getrandom_fill(&mut buf), Err(getrandom::Error::UNSUPPORTED));
+
+    assert_eq!(register_getrandom_backend(key(3)), Ok(()));
+    assert_eq!(register_getrandom_backend(key(4)), Err(AlreadyRegistered));
+
+    let mut expected = [0u8; 28];
+    fill_bytes(key(3), 0, &mut expected);
+
+    // words 0..2, then 2..3
+    getrandom_fill(&mut buf).unwrap();
+    assert_eq!(buf, expected[..12]);
+    let mut second = [0u8; 5];
+    getrandom_fill(&mut second).unwrap();
+    assert_eq!(second, expected[16..21]);
+
+    getrandom_fill(&mut []).unwrap();
+    let mut third = [0u8; 4];
+    getrandom_fill(&mut third).unwrap();
+    assert_eq!(third, expected[24..]);
+
+    // on targets where getrandom dispatches to custom handlers, it reaches this one
+    if cfg!(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))) {
+      let mut via_getrandom = [0u8; 8];
+      getrandom::getrandom(&mut via_getrandom).unwrap();
+      let mut word = [0u8; 8];
+      fill_bytes(key(3), 4, &mut word);
+      assert_eq!(via_getrandom, word);
+    }
+
+    // the counter runs out instead of wrapping
+    super::COUNTER.store(u32::MAX - 1, core::sync::atomic::Ordering::Relaxed);
+    let code = |r: Result<(), getrandom::Error>| r.unwrap_err().code().get();
+    assert_eq!(code(getrandom_fill(&mut [0; 9])), super::GETRANDOM_EXHAUSTED);
+    getrandom_fill(&mut [0; 8]).unwrap();
+    assert_eq!(code(getrandom_fill(&mut [0; 1])), super::GETRANDOM_EXHAUSTED);
+    getrandom_fill(&mut []).unwrap();
+  }
+}
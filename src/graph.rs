@@ -0,0 +1,149 @@
+#[cfg(feature = "alloc")] use alloc::collections::BTreeSet;
+use crate::{f64_open, Key};
+#[cfg(feature = "alloc")] use crate::bounded;
+
+/// The number of possible edges `n (n - 1) / 2` of a simple undirected graph on `n` nodes.
+///
+/// ## Panics
+/// If it does not fit in a `u64` (`n` above about `6.07e9`).
+fn edge_count(n: u64) -> u64 {
+  let edges = n as u128 * (n as u128).saturating_sub(1) / 2;
+  assert!(edges <= u64::MAX as u128, "graph: too many nodes");
+  edges as u64
+}
+
+/// The edge at position `e` of the linearized edge space: `(u, v)` with `u < v`,
+/// ordered by `v`, then `u`, so `e = v (v - 1) / 2 + u`.
+fn edge_at(e: u64) -> (u64, u64) {
+  let tri = |v: u64| v as u128 * (v as u128 - 1) / 2;
+  let mut v = ((1.0 + libm::sqrt(1.0 + 8.0 * e as f64)) / 2.0) as u64;
+  // correct the floating point estimate
+  while v > 1 && tri(v) > e as u128 { v -= 1; }
+  while tri(v + 1) <= e as u128 { v += 1; }
+  ((e as u128 - tri(v)) as u64, v)
+}
+
+/// The edges of an Erdős–Rényi G(`n`, `p`) random graph: each of the `n (n - 1) / 2` possible edges
+/// `(u, v)`, `u < v`, is present independently with probability `p`.
+///
+/// Edges come in increasing `v`, then `u`. Rather than a draw per possible edge, the gaps between
+/// present edges are drawn from the geometric distribution (Batagelj and Brandes): gap `s` is taken from
+/// [`f64_open`] at `index + s`, so work and counters consumed are proportional to the edges produced.
+///
+/// ## Panics
+/// If `p` is not in `[0, 1]`, or `n (n - 1) / 2` does not fit in a `u64`.
+pub fn gnp_edges(key: Key, index: u64, n: u64, p: f64) -> impl Iterator<Item = (u64, u64)> {
+  assert!((0.0..=1.0).contains(&p), "gnp_edges: p must be in [0, 1]");
+  let edges = edge_count(n);
+  let log_keep = libm::log1p(-p);
+
+  // `p == 0` would divide by zero below
+  let mut next = if p == 0.0 { edges } else { 0 };
+  let mut s = 0u64;
+  core::iter::from_fn(move || {
+    if next >= edges { return None; }
+    // saturating float-to-int conversion: huge gaps just end the walk
+    next = next.saturating_add((libm::log(f64_open(key, index.wrapping_add(s))) / log_keep) as u64);
+    s += 1;
+    if next >= edges { return None; }
+    let edge = edge_at(next);
+    next += 1;
+    Some(edge)
+  })
+}
+
+/// The edges of an Erdős–Rényi G(`n`, `m`) random graph: exactly `m` distinct edges `(u, v)`, `u < v`,
+/// chosen uniformly among the `n (n - 1) / 2` possible (feature `alloc`).
+///
+/// Edges come in increasing `v`, then `u`. Positions in the edge space are chosen by Floyd's
+/// algorithm, exactly as [`sample_vec`](crate::sample_vec) draws them (draw `s` is [`bounded`] at `index + s`),
+/// keeping `m` positions in memory.
+///
+/// ## Panics
+/// If `m` exceeds the number of possible edges, or that does not fit in a `u64`.
+#[cfg(feature = "alloc")]
+pub fn gnm_edges(key: Key, index: u64, n: u64, m: u64) -> impl Iterator<Item = (u64, u64)> {
+  let edges = edge_count(n);
+  assert!(m <= edges, "gnm_edges: m exceeds the number of possible edges");
+
+  let mut chosen = BTreeSet::new();
+  for (s, j) in (edges - m..edges).enumerate() {
+    let t = bounded(key, index.wrapping_add(s as u64), j + 1);
+    if !chosen.insert(t) { chosen.insert(j); }
+  }
+  chosen.into_iter().map(edge_at)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::vec::Vec;
+  use crate::key;
+  use super::{edge_at, gnp_edges};
+
+  #[test]
+  fn edge_space_order() {
+    let mut e = 0;
+    for v in 1..200 {
+      for u in 0..v {
+        assert_eq!(edge_at(e), (u, v));
+        e += 1;
+      }
+    }
+    // rounding near the top of the space
+    let n = 6_000_000_000u64;
+    let last = (n as u128 * (n as u128 - 1) / 2 - 1) as u64;
+    assert_eq!(edge_at(last), (n - 2, n - 1));
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn gnp_edge_count_and_degrees() {
+    let (n, p) = (2_000, 0.01);
+    let edges: Vec<_> = gnp_edges(key(31), 0, n, p).collect();
+
+    // 1,999,000 possible edges: mean 19,990, standard deviation about 140
+    assert!((edges.len() as f64 - 19_990.0).abs() < 700.0, "{}", edges.len());
+    assert!(edges.iter().all(|&(u, v)| u < v && v < n));
+    assert!(edges.windows(2).all(|w| (w[0].1, w[0].0) < (w[1].1, w[1].0)), "not strictly increasing");
+
+    // degrees are Binomial(n - 1, p): mean and variance about 19.8
+    let mut degree = std::vec![0u32; n as usize];
+    for &(u, v) in &edges { degree[u as usize] += 1; degree[v as usize] += 1; }
+    let mean = degree.iter().map(|&d| d as f64).sum::<f64>() / n as f64;
+    let var = degree.iter().map(|&d| (d as f64 - mean).powi(2)).sum::<f64>() / n as f64;
+    assert!((mean - 19.99).abs() < 0.7, "{}", mean);
+    assert!((var - 19.79).abs() < 3.0, "{}", var);
+
+    assert!(gnp_edges(key(31), 0, n, p).eq(edges.iter().copied()));
+    assert!(!gnp_edges(key(31), 1, n, p).eq(edges.iter().copied()));
+  }
+
+  #[test]
+  fn gnp_extremes() {
+    assert_eq!(gnp_edges(key(31), 0, 50, 0.0).count(), 0);
+    assert!(gnp_edges(key(31), 0, 50, 1.0).eq((0..50 * 49 / 2).map(edge_at)));
+    assert_eq!(gnp_edges(key(31), 0, 0, 0.5).count(), 0);
+    assert_eq!(gnp_edges(key(31), 0, 1, 1.0).count(), 0);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn gnm_exact() {
+    use crate::sample_vec;
+    use super::gnm_edges;
+
+    let k = key(32);
+    let edges: Vec<_> = gnm_edges(k, 5, 1_000, 3_000).collect();
+    assert_eq!(edges.len(), 3_000);
+    assert!(edges.iter().all(|&(u, v)| u < v && v < 1_000));
+    assert!(edges.windows(2).all(|w| (w[0].1, w[0].0) < (w[1].1, w[1].0)), "duplicate or out of order");
+
+    // the same positions `sample_vec` chooses
+    let positions = sample_vec(k, 5, 1_000 * 999 / 2, 3_000);
+    assert!(positions.into_iter().map(edge_at).eq(edges.iter().copied()));
+    assert!(gnm_edges(k, 5, 1_000, 3_000).eq(edges.iter().copied()));
+
+    assert_eq!(gnm_edges(k, 0, 10, 45).count(), 45);
+    assert_eq!(gnm_edges(k, 0, 10, 0).count(), 0);
+  }
+}
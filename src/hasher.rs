@@ -0,0 +1,110 @@
+use core::hash::{BuildHasher, Hasher};
+use crate::{u64, Key};
+
+/// the state a hash starts from
+const HASH_INIT: u64 = 0x9e3779b97f4a7c15;
+
+/// A [`BuildHasher`] for `HashMap`s and `HashSet`s whose iteration order is shuffled by `key`
+/// yet reproducible from it:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use squares::{key, SquaresBuildHasher};
+///
+/// let mut map = HashMap::with_hasher(SquaresBuildHasher::new(key(0)));
+/// map.insert("a", 1);
+/// ```
+///
+/// ## Warning:
+/// Not DoS-resistant: Squares is not a cryptographic function, so anyone who can watch collisions
+/// or timings may be able to recover enough to flood a table. Use `std`'s `RandomState` for
+/// untrusted input.
+#[derive(Clone, Copy, Debug)]
+pub struct SquaresBuildHasher(Key);
+
+impl SquaresBuildHasher {
+  #[must_use]
+  pub const fn new(key: Key) -> Self { Self(key) }
+}
+
+impl BuildHasher for SquaresBuildHasher {
+  type Hasher = SquaresHasher;
+
+  #[inline]
+  fn build_hasher(&self) -> SquaresHasher {
+    SquaresHasher { key: self.0, state: HASH_INIT, tail: 0, len: 0 }
+  }
+}
+
+/// The [`Hasher`] of [`SquaresBuildHasher`]: written bytes are absorbed as little-endian `u64` words
+/// through `state = u64(key, state ^ word)`, and `finish` absorbs the zero-padded last word and then
+/// the total length. Only the byte stream matters, not how it was split across `write` calls.
+#[derive(Clone, Debug)]
+pub struct SquaresHasher {
+  key: Key,
+  state: u64,
+  /// bytes of the current word not yet absorbed, in the low `len % 8` bytes
+  tail: u64,
+  /// bytes written so far
+  len: u64,
+}
+
+impl Hasher for SquaresHasher {
+  #[inline]
+  fn write(&mut self, bytes: &[u8]) {
+    for &b in bytes {
+      self.tail |= (b as u64) << (8 * (self.len % 8));
+      self.len = self.len.wrapping_add(1);
+      if self.len.is_multiple_of(8) {
+        self.state = u64(self.key, self.state ^ self.tail);
+        self.tail = 0;
+      }
+    }
+  }
+
+  #[inline]
+  fn finish(&self) -> u64 {
+    let state = if self.len.is_multiple_of(8) { self.state } else { u64(self.key, self.state ^ self.tail) };
+    u64(self.key, state ^ self.len)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::hash::{BuildHasher, Hasher};
+  use crate::key;
+  use super::SquaresBuildHasher;
+
+  fn hash(build: &SquaresBuildHasher, chunks: &[&[u8]]) -> u64 {
+    let mut h = build.build_hasher();
+    for c in chunks { h.write(c); }
+    h.finish()
+  }
+
+  #[test]
+  fn hashes_reproducible() {
+    let build = SquaresBuildHasher::new(key(37));
+    let bytes = b"the quick brown fox";
+    assert_eq!(hash(&build, &[bytes]), hash(&build, &[bytes]));
+    assert_eq!(hash(&build, &[bytes]), hash(&SquaresBuildHasher::new(key(37)), &[bytes]));
+    // only the stream matters
+    assert_eq!(hash(&build, &[bytes]), hash(&build, &[&bytes[..3], &bytes[3..11], &bytes[11..]]));
+
+    assert_ne!(hash(&build, &[bytes]), hash(&SquaresBuildHasher::new(key(38)), &[bytes]));
+    assert_ne!(hash(&build, &[b"ab"]), hash(&build, &[b"ab\0"]));
+    assert_ne!(hash(&build, &[]), hash(&build, &[&[0; 8]]));
+    assert_eq!(build.hash_one("x"), build.hash_one("x"));
+  }
+
+  #[test]
+  fn hash_map_order_reproducible() {
+    use std::{collections::HashMap, vec::Vec};
+    let order = |k| {
+      let mut map = HashMap::with_hasher(SquaresBuildHasher::new(key(k)));
+      for i in 0..100u32 { map.insert(i, ()); }
+      map.into_keys().collect::<Vec<_>>()
+    };
+    assert_eq!(order(37), order(37));
+    assert_ne!(order(37), order(38));
+  }
+}
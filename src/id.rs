@@ -0,0 +1,234 @@
+use crate::{derive, u32, u64, Key};
+
+/// Produces 16 bytes laid out as an RFC 4122 version 4 (random) UUID: `u64(key, index)` then
+/// `u64(key, derive(index, 1))` (where `derive(index, k)` is `u64(DERIVE_KEY, index) + k`), big-endian,
+/// with the version nibble set to `4` and the variant bits to `10`.
+///
+/// These are deterministic: unique only as far as `(key, index)` pairs are, and reproducible by
+/// anyone holding the key. Format with any UUID library, e.g. `uuid::Uuid::from_bytes`.
+#[must_use] #[inline]
+pub const fn uuid_bytes(key: Key, index: u64) -> [u8; 16] {
+  let hi = u64(key, index).to_be_bytes();
+  let lo = u64(key, derive(index, 1)).to_be_bytes();
+
+  let mut out = [0; 16];
+  let mut i = 0;
+  while i < 8 {
+    out[i] = hi[i];
+    out[8 + i] = lo[i];
+    i += 1;
+  }
+  out[6] = (out[6] & 0x0f) | 0x40;
+  out[8] = (out[8] & 0x3f) | 0x80;
+  out
+}
+
+/// Fills `out` with unbiased indices into a word list of `list_len` entries (e.g. 7776 for the EFF
+/// Diceware list), for passphrases; returns the next unused counter, to continue from later.
+///
+/// Each index is a widening multiply of [`u32()`] at the next counter, starting at `index`;
+/// a rejected draw (probability below `list_len / 2^32`) moves on to the following counter, so
+/// `out.len()` plus the number of rejections are consumed.
+///
+/// ## Panics
+/// If `list_len == 0`.
+pub fn wordlist_indices(key: Key, index: u64, list_len: u32, out: &mut [u32]) -> u64 {
+  assert!(list_len != 0, "wordlist_indices: list_len must be non-zero");
+  let threshold = list_len.wrapping_neg() % list_len;
+
+  let mut counter = index;
+  for word in out {
+    loop {
+      let m = u32(key, counter) as u64 * list_len as u64;
+      counter = counter.wrapping_add(1);
+      if m as u32 >= threshold {
+        *word = (m >> 32) as u32;
+        break;
+      }
+    }
+  }
+  counter
+}
+
+/// Entropy each word uniformly chosen from a list of `list_len` contributes: `log2(list_len)` bits
+/// (about 12.9 for 7776 words, so six words give 77.5 bits).
+#[must_use]
+pub fn bits_per_word(list_len: u32) -> f64 {
+  libm::log2(list_len as f64)
+}
+
+/// The base62 alphabet: digits, then upper case, then lower case.
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Maps a draw to an ASCII byte of `[0-9A-Za-z]`, picked by `draw % 62`.
+///
+/// The modulo bias is below `62 / 2^64`, far beneath anything observable.
+#[must_use] #[inline(always)]
+pub const fn base62_char(draw: u64) -> u8 {
+  BASE62[(draw % 62) as usize]
+}
+
+/// Writes `len` base62 characters into `out[..len]`, character `i` being
+/// [`base62_char`]`(u64(key, start_index + i))`; the bytes are ASCII, so always valid UTF-8.
+///
+/// ## Panics
+/// If `len > out.len()`.
+pub fn base62_string(key: Key, start_index: u64, len: usize, out: &mut [u8]) {
+  assert!(len <= out.len(), "base62_string: len exceeds the buffer");
+  for (i, c) in out[..len].iter_mut().enumerate() {
+    *c = base62_char(u64(key, start_index.wrapping_add(i as u64)));
+  }
+}
+
+/// An `N`-byte base62 token (`[0-9A-Za-z]`, as [`base62_char`]), computable in `const` context,
+/// e.g. to bake a device token into firmware.
+///
+/// Unlike [`base62_string`], the characters are exactly uniform: byte `i` takes the first 6-bit chunk
+/// below 62 from `u64(key, derive(start_index + i, t))` for `t = 0, 1, ...`, scanning each draw from the low bits
+/// (ten chunks per draw, so a second draw is needed with probability below `2^-50`). `N` indices are consumed.
+///
+/// ```
+/// const TOKEN: [u8; 12] = squares::token(squares::key(7), 0);
+/// assert!(TOKEN.iter().all(u8::is_ascii_alphanumeric));
+/// ```
+#[must_use]
+pub const fn token<const N: usize>(key: Key, start_index: u64) -> [u8; N] {
+  let mut out = [0; N];
+  let mut i = 0;
+  while i < N {
+    let index = start_index.wrapping_add(i as u64);
+    let mut t = 0;
+    'draws: loop {
+      let draw = u64(key, derive(index, t));
+      let mut chunk = 0;
+      while chunk < 10 {
+        let v = (draw >> (6 * chunk)) & 63;
+        if v < 62 {
+          out[i] = BASE62[v as usize];
+          break 'draws;
+        }
+        chunk += 1;
+      }
+      t += 1;
+    }
+    i += 1;
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u32, u64};
+  use super::{base62_char, base62_string, bits_per_word, token, uuid_bytes, wordlist_indices};
+
+  #[test]
+  fn token_in_const() {
+    const TOKEN: [u8; 12] = token(key(42), 3);
+    const VALID: bool = {
+      let mut ok = true;
+      let mut i = 0;
+      while i < 12 {
+        ok &= TOKEN[i].is_ascii_alphanumeric();
+        i += 1;
+      }
+      ok
+    };
+    const _: () = assert!(VALID);
+    assert_eq!(TOKEN, token::<12>(key(42), 3));
+    // a prefix of a longer token
+    assert_eq!(TOKEN[..], token::<20>(key(42), 3)[..12]);
+    let d = u64(key(42), 3);
+    let v = (0..10).map(|c| (d >> (6 * c)) & 63).find(|&v| v < 62).unwrap();
+    assert_eq!(TOKEN[0], super::BASE62[v as usize]);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn token_uniform() {
+    let mut counts = [0u32; 128];
+    for i in 0..100 {
+      for c in token::<62>(key(42), i * 62) { counts[c as usize] += 1; }
+    }
+    // 100 per character; 5 standard deviations is 50
+    assert!(counts.iter().filter(|&&c| c != 0).all(|&c| (50..150).contains(&c)), "{:?}", counts);
+    assert_eq!(counts.iter().filter(|&&c| c != 0).count(), 62);
+  }
+
+  #[test]
+  fn base62_alphabet() {
+    let mut out = [0; 4_096];
+    base62_string(key(26), 3, out.len(), &mut out);
+    assert!(out.iter().all(u8::is_ascii_alphanumeric));
+    assert!(core::str::from_utf8(&out).is_ok());
+    assert_eq!(out[0], base62_char(u64(key(26), 3)));
+
+    // every character is reachable
+    let mut seen = [false; 128];
+    for c in out { seen[c as usize] = true; }
+    assert_eq!(seen.iter().filter(|&&s| s).count(), 62);
+
+    assert_eq!((base62_char(0), base62_char(10), base62_char(36), base62_char(61)), (b'0', b'A', b'a', b'z'));
+
+    // only the first `len` bytes are written
+    let mut short = [0; 8];
+    base62_string(key(26), 3, 5, &mut short);
+    assert_eq!((&short[..5], &short[5..]), (&out[..5], &[0; 3][..]));
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn wordlist_indices_uniform() {
+    let k = key(25);
+    let mut out = [0; 7_000];
+    assert_eq!(wordlist_indices(k, 10, 7, &mut out), 10 + 7_000);
+    let mut counts = [0; 7];
+    for &w in &out { counts[w as usize] += 1; }
+    assert!(counts.iter().all(|&c| (870..1_130).contains(&c)), "{:?}", counts);
+
+    let mut words = [0; 6];
+    wordlist_indices(k, 0, 7776, &mut words);
+    assert!(words.iter().all(|&w| w < 7776));
+    assert_eq!(words[0], ((u32(k, 0) as u64 * 7776) >> 32) as u32);
+  }
+
+  #[test]
+  fn wordlist_rejection_consumes_counters() {
+    let k = key(25);
+    // 2^31 + 1 entries reject almost half the draws
+    let len = (1 << 31) + 1;
+    let mut out = [0; 100];
+    let next = wordlist_indices(k, 5, len, &mut out);
+    let rejected = next - 5 - 100;
+    assert!((50..150).contains(&rejected), "{}", rejected);
+    assert!(out.iter().all(|&w| w < len));
+
+    // the same, continued in two halves
+    let mut first = [0; 40];
+    let mut second = [0; 60];
+    let mid = wordlist_indices(k, 5, len, &mut first);
+    assert_eq!(wordlist_indices(k, mid, len, &mut second), next);
+    assert_eq!((&out[..40], &out[40..]), (&first[..], &second[..]));
+  }
+
+  #[test]
+  fn bits_per_word_math() {
+    assert!((bits_per_word(7776) - 12.924_812).abs() < 1e-6);
+    assert!((6.0 * bits_per_word(7776) - 77.548_875).abs() < 1e-5);
+    assert_eq!(bits_per_word(1), 0.0);
+    assert_eq!(bits_per_word(1 << 20), 20.0);
+  }
+
+  #[test]
+  fn uuid_version_and_variant() {
+    let k = key(21);
+    for i in [0, 1, u64::MAX] {
+      let id = uuid_bytes(k, i);
+      assert_eq!(id[6] >> 4, 4);
+      assert_eq!(id[8] >> 6, 0b10);
+      // everything else is the draw
+      let hi = u64(k, i).to_be_bytes();
+      assert_eq!((&id[..6], id[6] & 0x0f, id[7]), (&hi[..6], hi[6] & 0x0f, hi[7]));
+    }
+    assert_ne!(uuid_bytes(k, 0), uuid_bytes(k, 1));
+  }
+}
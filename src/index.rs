@@ -0,0 +1,69 @@
+use crate::{u64, Key};
+
+/// spreads the 32 bits of `x` to the even bit positions of a `u64`
+const fn spread(x: u32) -> u64 {
+  let mut x = x as u64;
+  x = (x | (x << 16)) & 0x0000_ffff_0000_ffff;
+  x = (x | (x << 8)) & 0x00ff_00ff_00ff_00ff;
+  x = (x | (x << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+  x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+  x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+  x
+}
+
+/// Noise at 2D coordinate `(x, y)`: [`u64()`] of the Morton (Z-order) index,
+/// which interleaves the bits of `x` (even positions) and `y` (odd positions).
+/// Nearby coordinates map to nearby counters, which suits tiled or chunked terrain.
+#[must_use] #[inline(always)]
+pub const fn morton_noise(key: Key, x: u32, y: u32) -> u64 {
+  u64(key, spread(x) | (spread(y) << 1))
+}
+
+/// Reverses the bit order of `x` (bit `i` moves to `63 - i`), an index transform to compose with
+/// the generators: `u64(key, reverse_bits_u64(i))` visits counters in bit-reversed (van der Corput) order,
+/// so any prefix of the indices is spread evenly over the counter space.
+#[must_use] #[inline(always)]
+pub const fn reverse_bits_u64(x: u64) -> u64 {
+  x.reverse_bits()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u64};
+  use super::{morton_noise, reverse_bits_u64, spread};
+
+  #[test]
+  fn reverse_bits_involution() {
+    for x in [0, 1, 0x8000_0000_0000_0000, 0x0123_4567_89ab_cdef, u64::MAX, u64(key(46), 0)] {
+      assert_eq!(reverse_bits_u64(reverse_bits_u64(x)), x);
+    }
+    assert_eq!(reverse_bits_u64(1), 1 << 63);
+    assert_eq!(reverse_bits_u64(0b110), 0b011 << 61);
+  }
+
+  #[test]
+  fn morton_interleaves() {
+    assert_eq!(spread(0b1011), 0b1000101);
+    assert_eq!(spread(u32::MAX), 0x5555_5555_5555_5555);
+
+    let k = key(8);
+    assert_eq!(morton_noise(k, 0b11, 0b01), u64(k, 0b0111));
+    assert_eq!(morton_noise(k, u32::MAX, u32::MAX), u64(k, u64::MAX));
+  }
+
+  #[test]
+  fn morton_noise_reproducible() {
+    let k = key(8);
+    let mut seen = std::vec::Vec::new();
+    for x in 0..32 {
+      for y in 0..32 {
+        assert_eq!(morton_noise(k, x, y), morton_noise(k, x, y));
+        seen.push(morton_noise(k, x, y));
+      }
+    }
+    seen.sort_unstable();
+    seen.dedup();
+    assert_eq!(seen.len(), 32 * 32);
+    assert_ne!(morton_noise(k, 1, 2), morton_noise(k, 2, 1));
+  }
+}
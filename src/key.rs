@@ -15,6 +15,10 @@
 //!     the upper 7 nibbles must not contain the 9th nibble.
 //! 5. Inter-key:
 //!   - For any two keys, at least one nibble among the lower 9 will differ.
+//!
+//! Rules 1-4 hold for every key from [`key`]. Rule 5 is a requirement on keys *used together*,
+//! which [`key`] does not enforce across indices (roughly 1 pair in `3 * 10^8` collides);
+//! mint such sets with [`key_set`] or check them with [`verify_pairwise_distinct`].
 
 /// Holds an admissible Squares key
 /// 
@@ -30,6 +34,7 @@
 ///     the upper 7 nibbles must not contain the 9th nibble.
 /// 5. Inter-key:
 ///   - For any two keys, at least one nibble among the lower 9 will differ.
+///     [`key`] doesn't guarantee this across indices; see [`key_set`].
 #[derive(Clone, Copy, Debug)]
 pub struct Key(u64);
 
@@ -40,7 +45,7 @@ impl Key {
   pub const fn unchecked(key: u64) -> Self { Self(key) }
 
   /// Checks admissibility of the key value
-  #[must_use] #[inline(always)]
+  #[inline(always)]
   pub const fn checked(key: u64) -> Result<Self, Inadmissible> {
     match check_admissibility(key) {
       Ok(_) => Ok(Self(key)),
@@ -57,6 +62,48 @@ impl Key {
   /// Provides the naked key value
   #[must_use] #[inline(always)]
   pub const fn inner(self) -> u64 { self.0 }
+
+  /// Splits the key into `N` XOR shares, for storing in separate places:
+  /// `N - 1` random words `u64(entropy_key, index..)`, then the XOR of the key with all of them.
+  /// All `N` shares are needed to reconstruct the key (see [`Key::combine_shares`]),
+  /// and any `N - 1` of them are independent of it.
+  ///
+  /// This only splits confidentiality; it is not a threshold scheme, and losing one share loses the key.
+  /// `entropy_key` and `index` must be secret and used once, since they regenerate the random shares.
+  ///
+  /// ## Panics
+  /// If `N < 2`.
+  #[must_use]
+  pub const fn split_shares<const N: usize>(self, entropy_key: Key, index: u64) -> [u64; N] {
+    assert!(N >= 2, "Key::split_shares: needs at least 2 shares");
+    let mut shares = [0; N];
+    let mut last = self.0;
+    let mut i = 0;
+    while i < N - 1 {
+      shares[i] = super::u64(entropy_key, index.wrapping_add(i as u64));
+      last ^= shares[i];
+      i += 1;
+    }
+    shares[N - 1] = last;
+    shares
+  }
+
+  /// Reconstructs a key from all of its [`Key::split_shares`], checking the result is admissible,
+  /// which catches most missing or tampered shares.
+  pub const fn combine_shares(shares: &[u64]) -> Result<Key, Inadmissible> {
+    let mut k = 0;
+    let mut i = 0;
+    while i < shares.len() {
+      k ^= shares[i];
+      i += 1;
+    }
+    Key::checked(k)
+  }
+}
+
+impl From<Key> for u64 {
+  #[inline(always)]
+  fn from(key: Key) -> u64 { key.0 }
 }
 
 /// the key used to produce random admissible keys
@@ -71,8 +118,23 @@ const MASTER_KEY_B: Key = Key::unchecked(0x72f9c8a323a5e4f1);
 /// If your seed (`index`) is a small number, it may be possible for an adversary 
 /// to brute-force guess the key this function produces using only RNG outputs,
 /// allowing them to predict all RNG outputs.
-#[must_use]
+#[must_use] #[inline]
 pub const fn key(index: u64) -> Key {
+  key_from_draws(index, super::u64(MASTER_KEY_A, index), &[super::u32(MASTER_KEY_B, index)])
+}
+
+/// The `draw`-th upper-nibble word of the key at `index`: `u32(MASTER_KEY_B, index + draw)`,
+/// taken from `uppers` when it was precomputed there.
+#[inline(always)]
+const fn upper_draw(index: u64, draw: u64, uppers: &[u32]) -> u32 {
+  if draw < uppers.len() as u64 { return uppers[draw as usize]; }
+  super::u32(MASTER_KEY_B, index.wrapping_add(draw))
+}
+
+/// The body of [`key`], given its master draws: `destinations = u64(MASTER_KEY_A, index)`
+/// and a (possibly empty) prefix `uppers[j] = u32(MASTER_KEY_B, index + j)`.
+#[inline(always)]
+const fn key_from_draws(index: u64, destinations: u64, uppers: &[u32]) -> Key {
   // init list as 1..=15.
   let mut nibbles: [u8; 15] = [
     0x1, 0x2, 0x3, 0x4,
@@ -82,7 +144,6 @@ pub const fn key(index: u64) -> Key {
   ];
 
   // shuffle nibbles randomly
-  let destinations = super::u64(MASTER_KEY_A, index);
   let mut i = 14;
   while i > 0 {
     let dst = ((destinations >> (i * 4)) % 15) as usize;
@@ -93,10 +154,10 @@ pub const fn key(index: u64) -> Key {
   }
 
   // ensure first nibble odd
-  if nibbles[0] % 2 == 0 {
+  if nibbles[0] & 1 == 0 {
     let mut i = 1;
     while i < 15 {
-      if nibbles[i] % 2 == 1 {
+      if nibbles[i] & 1 == 1 {
         let v = nibbles[0];
         nibbles[0] = nibbles[i];
         nibbles[i] = v;
@@ -122,10 +183,7 @@ pub const fn key(index: u64) -> Key {
     let mut i = 9;
     while i < 15 {
       if nibbles[i] != nib_8 {
-        let t = nibbles[8];
-        nibbles[8] = nibbles[i];
-        nibbles[i] = t;
-        nib_9 = t;
+        nib_9 = nibbles[i];
         break;
       }
       i += 1;
@@ -135,8 +193,8 @@ pub const fn key(index: u64) -> Key {
   output |= (nib_9 as u64) << 32;
 
   // assign upper 7 nibbles (bits 36-63)
-  let mut rng_idx = index;
-  let mut upper = super::u32(MASTER_KEY_B, rng_idx);
+  let mut draw = 0;
+  let mut upper = upper_draw(index, draw, uppers);
   let mut i = 9;
   let mut j = 0;
   while i < 16 {
@@ -145,8 +203,8 @@ pub const fn key(index: u64) -> Key {
       j += 1;
       if j == 8 {
         j = 0;
-        rng_idx += 1;
-        upper = super::u32(MASTER_KEY_B, rng_idx)
+        draw += 1;
+        upper = upper_draw(index, draw, uppers)
       }
       nib = 1 + ((upper >> (j * 4)) % 15);
     }
@@ -154,8 +212,8 @@ pub const fn key(index: u64) -> Key {
     j += 1;
     if j == 8 {
       j = 0;
-      rng_idx += 1;
-      upper = super::u32(MASTER_KEY_B, rng_idx)
+      draw += 1;
+      upper = upper_draw(index, draw, uppers)
     }
 
     output |= (nib as u64) << (i * 4);
@@ -165,6 +223,202 @@ pub const fn key(index: u64) -> Key {
   Key(output)
 }
 
+/// An endless iterator of `key(start_index)`, `key(start_index + 1)`, ..., wrapping at `u64::MAX`.
+///
+/// Every key is admissible, but consecutive keys are not checked against each other under the
+/// inter-key rule; use [`key_set`] for a set that must be pairwise distinct.
+pub fn key_iter(start_index: u64) -> impl Iterator<Item = Key> {
+  core::iter::successors(Some(start_index), |i| Some(i.wrapping_add(1))).map(key)
+}
+
+/// keys assembled per batch by [`fill_keys`]
+const KEY_BATCH: usize = 64;
+
+/// Fills `out` with `key(start_index + i)` at position `i`.
+///
+/// Faster than calling [`key`] in a loop: the master draws are computed a batch at a time
+/// through [`fill_u64`](crate::fill_u64), and a key needing extra upper-nibble draws reuses
+/// the ones already computed for the keys after it.
+pub fn fill_keys(start_index: u64, out: &mut [Key]) {
+  let mut destinations = [0; KEY_BATCH];
+  let mut uppers = [0; KEY_BATCH + 1];
+
+  for (b, chunk) in out.chunks_mut(KEY_BATCH).enumerate() {
+    let index = start_index.wrapping_add((b * KEY_BATCH) as u64);
+    let n = chunk.len();
+    super::fill_u64(MASTER_KEY_A, index, &mut destinations[..n]);
+    for (j, u) in uppers[..n + 1].iter_mut().enumerate() {
+      *u = super::u32(MASTER_KEY_B, index.wrapping_add(j as u64));
+    }
+
+    for (i, k) in chunk.iter_mut().enumerate() {
+      *k = key_from_draws(index.wrapping_add(i as u64), destinations[i], &uppers[i..n + 1]);
+    }
+  }
+}
+
+/// Produces `[key(start_index), key(start_index + 1), ..]`, batched as in [`fill_keys`].
+#[must_use]
+pub fn key_array<const N: usize>(start_index: u64) -> [Key; N] {
+  let mut out = [Key(0); N];
+  fill_keys(start_index, &mut out);
+  out
+}
+
+/// Deterministically produces an admissible key from arbitrary bytes (e.g. a name or passphrase).
+/// The bytes are hashed to an index, which is passed to [`key`].
+///
+/// ## Warning:
+/// Short or guessable byte strings have the same weakness as small indices (see [`key`]).
+#[must_use] #[inline]
+pub const fn key_from_bytes(bytes: &[u8]) -> Key {
+  key(super::fold::fold_bytes(bytes))
+}
+
+/// Deterministically produces an admissible key from any number of `u64` seeds,
+/// e.g. a master seed combined with per-run parameters.
+///
+/// The seeds are folded in order by [`fold_u64`](crate::fold_u64) into one index for [`key`],
+/// so the result is *order-dependent*: `[a, b]` and `[b, a]` give unrelated keys,
+/// as do `[a]` and `[a, 0]`. The empty slice gives a fixed default key.
+#[must_use] #[inline]
+pub const fn key_from_seeds(seeds: &[u64]) -> Key {
+  key(super::fold::fold_u64(seeds))
+}
+
+/// Deterministically produces an admissible key from `index` within a named `domain`
+/// (e.g. a service name), so the same index in two domains gives unrelated keys.
+///
+/// The domain bytes are folded first and `index` is absorbed after them,
+/// so this is not the same as [`key_from_bytes`] on some concatenation.
+#[must_use] #[inline]
+pub const fn key_for_domain(index: u64, domain: &[u8]) -> Key {
+  use super::fold::{fold_bytes, fold_step};
+  key(fold_step(fold_bytes(domain), index))
+}
+
+/// Deterministically produces an admissible key for a pair of nested loop variables,
+/// e.g. a run seed `outer` and a worker number `inner`, from a single derivation index.
+///
+/// The index is Szudzik's pairing, `outer^2 + outer + inner` if `outer >= inner`, else
+/// `inner^2 + outer`, which is injective: for `outer, inner < 2^32` it fits in 64 bits and goes
+/// straight to [`key`], so distinct pairs give distinct indices. Larger pairs overflow 64 bits and
+/// are folded with [`fold_u64`](crate::fold_u64), where distinct pairs only collide by chance.
+///
+/// Small pairs give small indices, with the weakness described at [`key`].
+#[must_use] #[inline]
+pub const fn derive_nested(outer: u64, inner: u64) -> Key {
+  let (a, b) = (outer as u128, inner as u128);
+  let pair = if a >= b { a * a + a + b } else { b * b + a };
+  let index = if pair >> 64 == 0 { pair as u64 } else { super::fold::fold_u64(&[(pair >> 64) as u64, pair as u64]) };
+  key(index)
+}
+
+/// Cyclically rotates the 16 nibbles of `key` by `by` positions, toward the most significant:
+/// nibble `i` moves to position `(i + by) % 16`. Returns the raw value.
+///
+/// Useful for exploring the key space around a key, but the result is often *not* admissible
+/// (e.g. an even nibble may land in first position); check it with [`Key::checked`].
+#[must_use] #[inline(always)]
+pub const fn rotate_nibbles(key: Key, by: u32) -> u64 {
+  key.0.rotate_left((by % 16) * 4)
+}
+
+/// Produces a uniformly random odd nibble (`1, 3, 5, 7, 9, B, D, F`), as required of a key's first nibble.
+/// Taken from the top 3 bits of [`u32()`](crate::u32).
+#[must_use] #[inline(always)]
+pub const fn random_odd_nibble(key: Key, index: u64) -> u8 {
+  ((super::u32(key, index) >> 29) as u8) * 2 + 1
+}
+
+/// The nibble value the upper 7 nibbles of `key` must avoid (rule 4 of [`Key`]): its 9th nibble,
+/// for tooling that builds keys nibble by nibble.
+#[must_use] #[inline(always)]
+pub const fn forbidden_upper_nibble(key: Key) -> u8 {
+  ((key.0 >> 32) & 0xF) as u8
+}
+
+/// mask of the lower 9 nibbles, which rule 5 requires to differ between keys
+pub(crate) const INTER_KEY_MASK: u64 = (1 << 36) - 1;
+
+/// Checks the inter-key rule (rule 5 of [`Key`]) for a pair of keys:
+/// `true` if at least one of the lower 9 nibbles differs.
+#[must_use] #[inline(always)]
+pub const fn keys_distinct(a: Key, b: Key) -> bool {
+  (a.0 ^ b.0) & INTER_KEY_MASK != 0
+}
+
+/// Checks the inter-key rule across a set of keys.
+/// On failure, returns the positions of the first colliding pair found.
+pub fn verify_pairwise_distinct(keys: &[Key]) -> Result<(), (usize, usize)> {
+  for (i, &a) in keys.iter().enumerate() {
+    for (j, &b) in keys.iter().enumerate().skip(i + 1) {
+      if !keys_distinct(a, b) { return Err((i, j)); }
+    }
+  }
+  Ok(())
+}
+
+/// [`verify_pairwise_distinct`] for a fixed array, in `const` context too, so a baked-in key table
+/// can be checked against the inter-key rule at compile time:
+///
+/// ```
+/// use squares::{all_pairwise_distinct, key};
+/// const _: () = assert!(all_pairwise_distinct([key(0), key(1), key(2)]));
+/// ```
+#[must_use]
+pub const fn all_pairwise_distinct<const N: usize>(keys: [Key; N]) -> bool {
+  let mut i = 0;
+  while i < N {
+    let mut j = i + 1;
+    while j < N {
+      if !keys_distinct(keys[i], keys[j]) { return false; }
+      j += 1;
+    }
+    i += 1;
+  }
+  true
+}
+
+/// Searches `key(0)`, `key(1)`, ... `key(search_limit - 1)` for the first key whose most significant
+/// nibbles are `prefix_nibbles` (e.g. `&[0xC, 0x0, 0xF, 0xF, 0xE, 0xE]` for `0xc0ffee...`), for demos;
+/// returns `(index, key)`, or `None` if the limit is reached.
+///
+/// A brute-force scan: each extra nibble multiplies the expected search by about 15
+/// (nibbles are never `0`, so a prefix containing `0` is never found).
+///
+/// ## Panics
+/// If a value in `prefix_nibbles` is above `0xF`, or there are more than 16.
+#[must_use]
+pub fn vanity_key(prefix_nibbles: &[u8], search_limit: u64) -> Option<(u64, Key)> {
+  assert!(prefix_nibbles.len() <= 16, "vanity_key: a key has 16 nibbles");
+  assert!(prefix_nibbles.iter().all(|&n| n <= 0xF), "vanity_key: nibbles must be at most 0xF");
+  if prefix_nibbles.is_empty() { return if search_limit == 0 { None } else { Some((0, key(0))) }; }
+
+  let pattern = prefix_nibbles.iter().fold(0u64, |acc, &n| acc << 4 | n as u64);
+  let shift = 64 - 4 * prefix_nibbles.len() as u32;
+  (0..search_limit).map(|i| (i, key(i))).find(|&(_, k)| k.0 >> shift == pattern)
+}
+
+/// Fills `out` with admissible keys that pairwise satisfy the inter-key rule.
+///
+/// Takes `key(start_index)`, `key(start_index + 1)`, ... in order,
+/// skipping any key that collides with one already emitted.
+/// Returns the index after the last one consumed, to continue the set later.
+pub fn key_set(start_index: u64, out: &mut [Key]) -> u64 {
+  let mut index = start_index;
+  let mut filled = 0;
+  while filled < out.len() {
+    let k = key(index);
+    index = index.wrapping_add(1);
+    if out[..filled].iter().all(|&e| keys_distinct(e, k)) {
+      out[filled] = k;
+      filled += 1;
+    }
+  }
+  index
+}
+
 /// Represents a broken key rule
 /// 
 /// ### Properties of Admissible Keys:
@@ -179,6 +433,7 @@ pub const fn key(index: u64) -> Key {
 ///     the upper 7 nibbles must not contain the 9th nibble.
 /// 5. Inter-key:
 ///   - For any two keys, at least one nibble among the lower 9 will differ.
+///     [`key`] doesn't guarantee this across indices; see [`key_set`].
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Inadmissible {
   ContainsZeroNibble { position: usize, nibble: u8 },
@@ -200,7 +455,6 @@ impl Inadmissible {
   }
 }
 
-#[must_use]
 const fn check_admissibility(key: u64) -> Result<(), Inadmissible> {
   let mut nibbles: [u8; 16] = [0u8; 16];
   let mut i = 0;
@@ -232,7 +486,7 @@ const fn check_admissibility(key: u64) -> Result<(), Inadmissible> {
   }
 
   // check: first nibble must be odd
-  if nibbles[0] % 2 == 0 {
+  if nibbles[0] & 1 == 0 {
     return Err(Inadmissible::FirstNibbleEven { nibble: nibbles[0] });
   }
 
@@ -255,24 +509,450 @@ const fn check_admissibility(key: u64) -> Result<(), Inadmissible> {
   Ok(())
 }
 
+/// Checks that every value in `keys` is admissible, in `const` context too, so a baked-in key
+/// table can be validated at compile time:
+///
+/// ```
+/// const KEYS: [u64; 2] = [0x1dfd8627d5f26481, 0x53f888e736b1fac5];
+/// const _: () = assert!(squares::all_admissible(KEYS));
+/// ```
+///
+/// Only per-key rules are checked; see [`verify_pairwise_distinct`] for the inter-key rule.
+#[must_use]
+pub const fn all_admissible<const N: usize>(keys: [u64; N]) -> bool {
+  let mut i = 0;
+  while i < N {
+    if check_admissibility(keys[i]).is_err() { return false; }
+    i += 1;
+  }
+  true
+}
+
+/// the largest admissible key
+const MAX_ADMISSIBLE: u64 = 0xffff_fffe_fedc_ba97;
+
+/// Upper bound on the loop iterations of [`next_admissible`].
+///
+/// With the nibbles above it valid, position `p` can be blocked by at most `B(p)` values: its `0`,
+/// plus up to 7 forbidden values at the 9th nibble (`B = 8`), plus `7 - p` repeats in the lower 8
+/// (`B = 2, 2, 3, 4, 5, 6, 7` for positions 7 to 1), and at position 0 also the 7 even values
+/// (`B = 15`), with `B = 1` for the upper 7. A bump either moves the blocked nibble to its next value
+/// or carries into the one above, and each position is walked at most twice (carrying up from the
+/// first blocked nibble, then filling upward from `0` below where the carry stops, which always
+/// succeeds without carrying), so there are at most `2 * sum(B) = 118` bumps and one final check.
+const NEXT_ADMISSIBLE_MAX_ITERATIONS: u32 = 119;
+
+/// Returns the smallest admissible key greater than `key`, for enumerating the key space in order.
+///
+/// Rather than testing every value, each step finds the most significant nibble that no value
+/// sharing it (and the nibbles above it) could make admissible, and advances past that whole block.
+/// Each step is one pass over the 16 nibbles, and there are at most 119 of them, a proven but loose
+/// bound: the most seen over 100k random starting points is 47, and around 40 is typical.
+///
+/// ## Panics
+/// If no admissible key is greater than `key` (`key >= 0xfffffffefedcba97`).
+#[must_use]
+pub const fn next_admissible(key: u64) -> Key {
+  next_admissible_counted(key).0
+}
+
+/// [`next_admissible`], also returning how many iterations it took
+const fn next_admissible_counted(key: u64) -> (Key, u32) {
+  assert!(key < MAX_ADMISSIBLE, "next_admissible: no admissible key above this one");
+  let mut v = key + 1;
+  let mut iterations = 1;
+  loop {
+    match highest_blocked_nibble(v) {
+      None => return (Key::unchecked(v), iterations),
+      // every value below the bump shares the blocked prefix; it can't pass `MAX_ADMISSIBLE`,
+      // since that is admissible
+      Some(p) => v = ((v >> (4 * p)) + 1) << (4 * p),
+    }
+    iterations += 1;
+    debug_assert!(iterations <= NEXT_ADMISSIBLE_MAX_ITERATIONS);
+  }
+}
+
+/// The most significant nibble position of `v` that breaks a rule given only the nibbles above it
+/// (each rule relates a nibble to more significant ones), or `None` if `v` is admissible.
+const fn highest_blocked_nibble(v: u64) -> Option<u32> {
+  const fn nibble(v: u64, p: u32) -> u64 { (v >> (4 * p)) & 0xF }
+
+  let mut p = 16;
+  while p > 0 {
+    p -= 1;
+    let n = nibble(v, p);
+    if n == 0 || (p == 0 && n & 1 == 0) { return Some(p); }
+
+    // the nibbles `n` must differ from: the 9th from the upper 7, the lower 8 from each other,
+    // and the 8th from the 9th
+    let (mut q, end) = match p {
+      8 => (9, 16),
+      7 => (8, 9),
+      _ if p < 8 => (p + 1, 8),
+      _ => (16, 16),
+    };
+    while q < end {
+      if nibble(v, q) == n { return Some(p); }
+      q += 1;
+    }
+  }
+  None
+}
+
 #[cfg(test)]
 mod tests {
+  use core::ops::Range;
   use crate::u64;
-  use super::{check_admissibility, key, Key};
+  use super::{all_admissible, all_pairwise_distinct, check_admissibility, derive_nested, fill_keys, forbidden_upper_nibble, key, key_array, key_for_domain, key_from_bytes, key_from_seeds, key_iter, key_set, keys_distinct, next_admissible, random_odd_nibble, rotate_nibbles, vanity_key, verify_pairwise_distinct, Inadmissible, Key};
+
+  /// the key used to produce random indices for the admissibility tests
+  const IDX_KEY: Key = Key::unchecked(0x16d7358fe8d9a17b);
+
+  /// iteration count `n`, reduced under Miri
+  const fn iters(n: u64) -> u64 {
+    if cfg!(miri) { n / 256 + 1 } else { n }
+  }
+
+  /// panics with the index, derived key, and broken rule if `key(index)` is inadmissible
+  fn assert_admissible(index: u64) {
+    let k = key(index);
+    if let Err(e) = check_admissibility(k.inner()) {
+      panic!("key at index {} ({:#018x}) is inadmissible: {:?}", index, k.inner(), e);
+    }
+  }
+
+  /// The `part`-th of `parts` contiguous, disjoint slices of `0..total`
+  fn partition(total: u64, parts: u64, part: u64) -> Range<u64> {
+    let start = (total as u128 * part as u128 / parts as u128) as u64;
+    let end = (total as u128 * (part + 1) as u128 / parts as u128) as u64;
+    start..end
+  }
 
   #[test]
-  fn key_properties_100m() {
-    let idx_key = Key::checked(0x16d7358fe8d9a17b).unwrap();
+  fn forbidden_upper_nibble_is_ninth() {
+    let k = Key::unchecked(0xaf9ed4c87b8e4fa5);
+    assert_eq!(forbidden_upper_nibble(k), 0x8);
+    // changing an upper nibble to it breaks rule 4
+    let broken = (k.inner() & !(0xF << 48)) | 0x8 << 48;
+    assert!(matches!(check_admissibility(broken), Err(Inadmissible::NinthNibbleRepeated { position: 12, nibble: 8 })));
+  }
 
-    for i in 0..100_000_000 {
-      // produce a random index
-      let index = u64(idx_key, i);
+  #[test]
+  fn all_admissible_in_const() {
+    const VALID: bool = all_admissible([key(0).inner(), key(1).inner(), 0xaf9ed4c87b8e4fa5]);
+    const MIXED: bool = all_admissible([key(0).inner(), 0x1dfd8627d5f20481]);
+    const _: () = assert!(VALID && !MIXED);
+    assert!(all_admissible([]));
+  }
+
+  #[test]
+  fn vanity_key_finds_prefix() {
+    let (i, k) = vanity_key(&[0xA], 200).unwrap();
+    assert_eq!(k.inner() >> 60, 0xA);
+    assert_eq!(k.inner(), key(i).inner());
+    assert!((0..i).all(|j| key(j).inner() >> 60 != 0xA));
+
+    let (i, k) = vanity_key(&[0xC, 0x3], 5_000).unwrap();
+    assert_eq!(k.inner() >> 56, 0xC3);
+    assert_eq!(k.inner(), key(i).inner());
+
+    assert!(vanity_key(&[0x0], 1_000).is_none());
+    assert!(vanity_key(&[0xA], 0).is_none());
+    assert_eq!(vanity_key(&[], 1).map(|(i, _)| i), Some(0));
+  }
+
+  #[test]
+  fn all_pairwise_distinct_in_const() {
+    // differ only above the lower 9 nibbles
+    const CLASH: bool = all_pairwise_distinct([key(3), Key::unchecked(0x1234_567F_EDCB_A987), Key::unchecked(0x7654_321F_EDCB_A987)]);
+    const DISTINCT: bool = all_pairwise_distinct([key(0), key(1), key(2), Key::unchecked(0xaf9ed4c87b8e4fa5)]);
+    const _: () = assert!(DISTINCT && !CLASH);
+    assert!(all_pairwise_distinct::<0>([]));
+    assert!(all_pairwise_distinct([key(0)]));
+  }
+
+  #[test]
+  fn next_admissible_finds_successor() {
+    const FROM_ZERO: Key = next_admissible(0);
+    const _: () = assert!(FROM_ZERO.inner() > 0);
+    assert_eq!(check_admissibility(FROM_ZERO.inner()), Ok(()));
+    assert_eq!(FROM_ZERO.inner(), 0x1111_1112_1234_5679);
+
+    assert_eq!(check_admissibility(super::MAX_ADMISSIBLE), Ok(()));
+    assert_eq!(next_admissible(super::MAX_ADMISSIBLE - 1).inner(), super::MAX_ADMISSIBLE);
+
+    // against a linear scan, from admissible keys and from arbitrary values
+    for i in 0..iters(500) {
+      for start in [key(i).inner(), u64(IDX_KEY, i)] {
+        if start >= super::MAX_ADMISSIBLE { continue; }
+        let next = next_admissible(start).inner();
+        assert!(next > start);
+        assert_eq!(check_admissibility(next), Ok(()));
+        let scan_end = next.min(start.saturating_add(1 << 12));
+        assert!((start + 1..scan_end).all(|v| check_admissibility(v).is_err()), "{:#x}", start);
+      }
+    }
+
+    let caught = std::panic::catch_unwind(|| next_admissible(u64::MAX));
+    assert!(caught.is_err());
+  }
+
+  #[test]
+  fn next_admissible_within_bound() {
+    use super::{next_admissible_counted, NEXT_ADMISSIBLE_MAX_ITERATIONS as BOUND, MAX_ADMISSIBLE};
+
+    // the slowest start found by search, and the extremes
+    const SLOW: (Key, u32) = next_admissible_counted(0x0c97_38e9_d4d3_ec8e);
+    const _: () = assert!(SLOW.1 == 47 && SLOW.1 <= BOUND && SLOW.0.inner() > 0x0c97_38e9_d4d3_ec8e);
+    const _: () = assert!(next_admissible_counted(0).1 <= BOUND);
+    const _: () = assert!(next_admissible_counted(MAX_ADMISSIBLE - 1).1 <= BOUND);
+    assert_eq!(check_admissibility(SLOW.0.inner()), Ok(()));
+
+    for i in 0..iters(100_000) {
+      let start = u64(IDX_KEY, i);
+      if start >= MAX_ADMISSIBLE { continue; }
+      let (next, n) = next_admissible_counted(start);
+      assert!(n <= BOUND, "{:#x}: {} iterations", start, n);
+      assert!(n <= 47, "{:#x}: {} iterations, above the documented maximum seen", start, n);
+      assert_eq!(next.inner(), next_admissible(start).inner());
+    }
+  }
+
+  #[test]
+  fn partition_covers_range() {
+    for total in [0, 1, 7, 100, 1_000_003, 100_000_000, u64::MAX] {
+      for parts in [1, 2, 3, 8, 13, 64] {
+        let mut next = 0;
+        for part in 0..parts {
+          let r = partition(total, parts, part);
+          assert_eq!(r.start, next, "gap or overlap at part {} of {} (total {})", part, parts, total);
+          assert!(r.start <= r.end);
+          next = r.end;
+        }
+        assert_eq!(next, total);
+      }
+    }
+  }
+
+  #[test]
+  fn key_properties_structured() {
+    // low and high indices
+    for i in 0..iters(32_768) {
+      assert_admissible(i);
+      assert_admissible(u64::MAX - i);
+    }
+
+    // windows around every bit boundary
+    for b in 0..64 {
+      let edge = 1u64 << b;
+      for d in 0..=iters(64) {
+        assert_admissible(edge.wrapping_sub(d));
+        assert_admissible(edge.wrapping_add(d));
+      }
+    }
+
+    // random indices
+    for i in 0..iters(25_000) {
+      assert_admissible(u64(IDX_KEY, i));
+    }
+  }
+
+  #[test]
+  fn key_from_bytes_admissible() {
+    let names: [&[u8]; 5] = [b"", b"a", b"overworld", b"the quick brown fox", &[0xff; 33]];
+    for name in names {
+      let k = key_from_bytes(name);
+      assert!(check_admissibility(k.inner()).is_ok());
+      assert_eq!(k.inner(), key_from_bytes(name).inner());
+    }
+    assert_ne!(key_from_bytes(b"overworld").inner(), key_from_bytes(b"underworld").inner());
+  }
+
+  #[test]
+  fn random_odd_nibbles() {
+    let mut seen = [0u32; 16];
+    for i in 0..1_000 {
+      let n = random_odd_nibble(key(9), i);
+      assert!(n & 1 == 1 && n < 16, "{}", n);
+      seen[n as usize] += 1;
+    }
+    assert!((1..16).step_by(2).all(|n| seen[n] > 0), "{:?}", seen);
+  }
+
+  #[test]
+  fn shares_round_trip() {
+    let secret = key(77);
+    let entropy = key(78);
+
+    let two: [u64; 2] = secret.split_shares(entropy, 0);
+    assert_eq!(Key::combine_shares(&two).unwrap().inner(), secret.inner());
+    let five: [u64; 5] = secret.split_shares(entropy, 1 << 40);
+    assert_eq!(Key::combine_shares(&five).unwrap().inner(), secret.inner());
+    assert!(five.iter().all(|&s| s != secret.inner()));
+
+    // zeroing the lowest nibble of the reconstruction violates rule 1
+    let mut tampered = five;
+    tampered[2] ^= secret.inner() & 0xF;
+    assert!(matches!(Key::combine_shares(&tampered), Err(Inadmissible::ContainsZeroNibble { position: 0, .. })));
+    assert!(Key::combine_shares(&five[..4]).is_err());
+  }
 
-      // make a key with the index
-      let k = key(index);
-      if let Err(e) = check_admissibility(k.inner()) {
-        panic!("Key at index {} is invalid: {:?}", index, e);
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn shares_look_uniform() {
+    // each share position, over many splits of one key, has unbiased bits
+    let secret = key(77);
+    let n = 20_000;
+    let mut ones = [[0u32; 64]; 3];
+    for i in 0..n {
+      let shares: [u64; 3] = secret.split_shares(key(79), i * 3);
+      for (s, counts) in shares.iter().zip(&mut ones) {
+        for (b, c) in counts.iter_mut().enumerate() {
+          *c += (s >> b) as u32 & 1;
+        }
+      }
+    }
+    for counts in ones {
+      for c in counts {
+        // 5 standard deviations of Binomial(20k, 1/2)
+        assert!((c as i64 - n as i64 / 2).abs() < 355, "{}", c);
+      }
+    }
+  }
+
+  #[test]
+  fn batched_keys_match_scalar() {
+    let n = if cfg!(miri) { 70 } else { 1_000 };
+    for start in [0, 1 << 40, u64::MAX - 100] {
+      let mut keys = std::vec![Key(0); n];
+      fill_keys(start, &mut keys);
+      for (i, k) in keys.iter().enumerate() {
+        assert_eq!(k.inner(), key(start.wrapping_add(i as u64)).inner(), "{}", start.wrapping_add(i as u64));
+      }
+    }
+
+    let arr: [Key; 130] = key_array(u64::MAX - 3);
+    for (i, k) in arr.iter().enumerate() {
+      assert_eq!(k.inner(), key((u64::MAX - 3).wrapping_add(i as u64)).inner());
+    }
+    fill_keys(0, &mut []);
+  }
+
+  #[test]
+  fn nibble_rotation() {
+    let k = Key::unchecked(0xfedc_ba98_7654_3211);
+    assert_eq!(rotate_nibbles(k, 16), k.inner());
+    assert_eq!(rotate_nibbles(k, 0), k.inner());
+    assert_eq!(rotate_nibbles(k, 1), 0xedcb_a987_6543_211f);
+    assert_eq!(rotate_nibbles(k, 17), rotate_nibbles(k, 1));
+    assert_eq!(rotate_nibbles(Key::unchecked(rotate_nibbles(k, 5)), 11), k.inner());
+  }
+
+  #[test]
+  fn key_iter_matches_key() {
+    for (i, k) in key_iter(u64::MAX - 2).take(6).enumerate() {
+      assert!(check_admissibility(k.inner()).is_ok());
+      assert_eq!(k.inner(), key((u64::MAX - 2).wrapping_add(i as u64)).inner());
+    }
+  }
+
+  #[test]
+  fn key_from_seeds_order_dependent() {
+    let sets: [&[u64]; 6] = [&[], &[0], &[1, 2], &[2, 1], &[1, 2, 0], &[u64::MAX; 9]];
+    let keys = sets.map(key_from_seeds);
+    for (i, a) in keys.iter().enumerate() {
+      assert!(check_admissibility(a.inner()).is_ok());
+      assert_eq!(a.inner(), key_from_seeds(sets[i]).inner());
+      for b in &keys[i + 1..] {
+        assert_ne!(a.inner(), b.inner());
+      }
+    }
+  }
+
+  #[test]
+  fn key_into_u64() {
+    for i in [0, 1, u64::MAX] {
+      let k = key(i);
+      assert_eq!(u64::from(k), k.inner());
+      let x: u64 = k.into();
+      assert_eq!(x, k.inner());
+    }
+  }
+
+  #[test]
+  fn derive_nested_distinct() {
+    let mut seen = std::collections::HashSet::new();
+    for outer in (0..40).chain([1 << 31, u32::MAX as u64, u64::MAX]) {
+      for inner in (0..40).chain([1 << 31, u32::MAX as u64, u64::MAX]) {
+        let k = derive_nested(outer, inner);
+        assert!(check_admissibility(k.inner()).is_ok());
+        assert!(seen.insert(k.inner()), "{} {}", outer, inner);
       }
     }
+    // the pairing is not symmetric
+    assert_ne!(derive_nested(1, 2).inner(), derive_nested(2, 1).inner());
+    assert_eq!(derive_nested(0, 0).inner(), key(0).inner());
+    assert_eq!(derive_nested(3, 1).inner(), key(13).inner());
+    assert_eq!(derive_nested(1, 3).inner(), key(10).inner());
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn key_for_domain_separates() {
+    let domains: [&[u8]; 4] = [b"", b"billing", b"search", b"search\0"];
+    for index in [0, 1, 42, u64::MAX] {
+      let keys = domains.map(|d| key_for_domain(index, d));
+      for (i, a) in keys.iter().enumerate() {
+        assert!(check_admissibility(a.inner()).is_ok());
+        assert_eq!(a.inner(), key_for_domain(index, domains[i]).inner());
+        assert_ne!(a.inner(), key(index).inner());
+        for b in &keys[i + 1..] {
+          assert!(keys_distinct(*a, *b), "{:?} {:?}", a, b);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn inter_key_rule() {
+    let a = Key::unchecked(0x4444_444F_EDCB_A987);
+    let same_lower = Key::unchecked(0x1234_567F_EDCB_A987);
+    let differs_9th = Key::unchecked(0x4444_444E_EDCB_A987);
+    assert!(!keys_distinct(a, same_lower));
+    assert!(keys_distinct(a, differs_9th));
+
+    assert_eq!(verify_pairwise_distinct(&[a, differs_9th, same_lower]), Err((0, 2)));
+    assert_eq!(verify_pairwise_distinct(&[a, differs_9th]), Ok(()));
+    assert_eq!(verify_pairwise_distinct(&[]), Ok(()));
+
+    let mut set = [Key::unchecked(0); 200];
+    let next = key_set(10, &mut set);
+    assert!(next >= 210);
+    assert_eq!(verify_pairwise_distinct(&set), Ok(()));
+    assert_eq!(set[0].inner(), key(10).inner());
+  }
+
+  /// Exhaustive run over 100M random indices.
+  /// Run with `cargo test --release -- --ignored`, and add `--features parallel-tests` to use all cores.
+  #[cfg(not(miri))]
+  #[test]
+  #[ignore]
+  fn key_properties_100m() {
+    const TOTAL: u64 = 100_000_000;
+
+    let check = |r: Range<u64>| for i in r { assert_admissible(u64(IDX_KEY, i)) };
+
+    #[cfg(feature = "parallel-tests")]
+    {
+      let parts = std::thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+      std::thread::scope(|s| {
+        for part in 0..parts {
+          s.spawn(move || check(partition(TOTAL, parts, part)));
+        }
+      });
+    }
+
+    #[cfg(not(feature = "parallel-tests"))]
+    check(partition(TOTAL, 1, 0));
+  }
+}
@@ -0,0 +1,270 @@
+#[cfg(feature = "critical-section")] use core::cell::RefCell;
+#[cfg(feature = "std")] use std::{collections::HashSet, sync::{atomic::{AtomicU64, Ordering}, Mutex}};
+use crate::{key, u64, Key, INTER_KEY_MASK};
+
+/// Consecutive occupied candidates after which allocation gives up: by then the occupancy set
+/// is almost surely over 99.8% full
+const MAX_REJECTS: u32 = 4096;
+
+/// The candidate keys every pool under `root` tries in order
+#[must_use] #[inline(always)]
+const fn candidate(root: Key, c: u64) -> Key {
+  key(u64(root, c))
+}
+
+/// A thread-safe source of keys that are pairwise distinct (rule 5 of [`Key`]) from every key
+/// it has ever handed out, for workers spawned and retired at runtime.
+///
+/// Candidate `c` is `key(u64(root, c))`, `c` taken from an atomic counter; a candidate whose
+/// lower 9 nibbles are already occupied is skipped. Keys are never returned to the pool, so a
+/// retired worker's key stays reserved. Memory grows by one set entry per key allocated.
+///
+/// Admissible keys have 1,937,295,360 distinct lower-9-nibble patterns, so no pool can hold more;
+/// see [`allocate`](Self::allocate) for what happens as it fills. Without `std`, see [`FixedKeyPool`].
+#[cfg(feature = "std")]
+pub struct KeyPool {
+  root: Key,
+  next: AtomicU64,
+  allocated: AtomicU64,
+  occupied: Mutex<HashSet<u64>>,
+}
+
+#[cfg(feature = "std")]
+impl KeyPool {
+  /// An empty pool drawing its candidates under `root`.
+  #[must_use]
+  pub fn new(root: Key) -> Self {
+    Self { root, next: AtomicU64::new(0), allocated: AtomicU64::new(0), occupied: Mutex::new(HashSet::new()) }
+  }
+
+  /// The next key distinct from all keys previously allocated or [`reserve`](Self::reserve)d,
+  /// or `None` once 4096 candidates in a row were occupied, i.e. the pool is (nearly) exhausted.
+  pub fn allocate(&self) -> Option<Key> {
+    for _ in 0..MAX_REJECTS {
+      let candidate = candidate(self.root, self.next.fetch_add(1, Ordering::Relaxed));
+      if self.claim(candidate) {
+        self.allocated.fetch_add(1, Ordering::Relaxed);
+        return Some(candidate);
+      }
+    }
+    None
+  }
+
+  /// Marks `key` as occupied without allocating it, e.g. for keys assigned statically elsewhere,
+  /// so the pool never hands out one colliding with it.
+  /// Returns `false` if its lower 9 nibbles were already occupied.
+  pub fn reserve(&self, key: Key) -> bool {
+    self.claim(key)
+  }
+
+  /// The number of keys [`allocate`](Self::allocate) has returned.
+  #[must_use]
+  pub fn allocated(&self) -> u64 {
+    self.allocated.load(Ordering::Relaxed)
+  }
+
+  fn claim(&self, key: Key) -> bool {
+    // the set is only ever inserted into, so a panicking holder cannot leave it inconsistent
+    let mut occupied = self.occupied.lock().unwrap_or_else(|e| e.into_inner());
+    occupied.insert(key.inner() & INTER_KEY_MASK)
+  }
+}
+
+/// A fixed-capacity [`KeyPool`] for `no_std` without `alloc`: room for `N` keys (allocated or
+/// reserved), scanned linearly, and handing out the same candidates as a `KeyPool` with the same root.
+///
+/// Its methods take `&mut self`, so to share one between threads or interrupt handlers, put it
+/// behind whatever lock the target has, or use `SharedKeyPool` (feature `critical-section`).
+#[derive(Clone, Debug)]
+pub struct FixedKeyPool<const N: usize> {
+  root: Key,
+  next: u64,
+  allocated: u64,
+  len: usize,
+  occupied: [u64; N],
+}
+
+impl<const N: usize> FixedKeyPool<N> {
+  /// An empty pool drawing its candidates under `root`.
+  #[must_use]
+  pub const fn new(root: Key) -> Self {
+    Self { root, next: 0, allocated: 0, len: 0, occupied: [0; N] }
+  }
+
+  /// The next key distinct from all keys previously allocated or [`reserve`](Self::reserve)d,
+  /// or `None` if the pool is full or 4096 candidates in a row were occupied.
+  pub fn allocate(&mut self) -> Option<Key> {
+    if self.len == N { return None; }
+    for _ in 0..MAX_REJECTS {
+      let candidate = candidate(self.root, self.next);
+      self.next = self.next.wrapping_add(1);
+      if self.claim(candidate) {
+        self.allocated += 1;
+        return Some(candidate);
+      }
+    }
+    None
+  }
+
+  /// Marks `key` as occupied without allocating it, so the pool never hands out one colliding with it.
+  /// Returns `false` if its lower 9 nibbles were already occupied, or the pool is full.
+  pub fn reserve(&mut self, key: Key) -> bool {
+    self.claim(key)
+  }
+
+  /// The number of keys [`allocate`](Self::allocate) has returned.
+  #[must_use]
+  pub const fn allocated(&self) -> u64 { self.allocated }
+
+  fn claim(&mut self, key: Key) -> bool {
+    let lower = key.inner() & INTER_KEY_MASK;
+    if self.len == N || self.occupied[..self.len].contains(&lower) { return false; }
+    self.occupied[self.len] = lower;
+    self.len += 1;
+    true
+  }
+}
+
+/// A [`FixedKeyPool`] behind a `critical_section::Mutex` (feature `critical-section`), so it can be
+/// a `static` shared by threads, cores and interrupt handlers.
+///
+/// Every call runs in a critical section, which scans up to `N` occupied entries (see [`FixedKeyPool`]).
+#[cfg(feature = "critical-section")]
+pub struct SharedKeyPool<const N: usize> {
+  pool: critical_section::Mutex<RefCell<FixedKeyPool<N>>>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<const N: usize> SharedKeyPool<N> {
+  /// An empty pool drawing its candidates under `root`.
+  #[must_use]
+  pub const fn new(root: Key) -> Self {
+    Self { pool: critical_section::Mutex::new(RefCell::new(FixedKeyPool::new(root))) }
+  }
+
+  /// As [`FixedKeyPool::allocate`].
+  pub fn allocate(&self) -> Option<Key> {
+    critical_section::with(|cs| self.pool.borrow_ref_mut(cs).allocate())
+  }
+
+  /// As [`FixedKeyPool::reserve`].
+  pub fn reserve(&self, key: Key) -> bool {
+    critical_section::with(|cs| self.pool.borrow_ref_mut(cs).reserve(key))
+  }
+
+  /// The number of keys [`allocate`](Self::allocate) has returned.
+  #[must_use]
+  pub fn allocated(&self) -> u64 {
+    critical_section::with(|cs| self.pool.borrow_ref(cs).allocated())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u64, Key};
+  use super::FixedKeyPool;
+
+  #[cfg(feature = "std")]
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn concurrent_allocations_distinct() {
+    use std::{collections::HashSet, vec::Vec};
+    use crate::INTER_KEY_MASK;
+    use super::KeyPool;
+
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 2_000;
+    let pool = KeyPool::new(key(27));
+
+    let keys: Vec<u64> = std::thread::scope(|s| {
+      let handles: Vec<_> = (0..THREADS)
+        .map(|_| s.spawn(|| (0..PER_THREAD).map(|_| pool.allocate().unwrap().inner()).collect::<Vec<_>>()))
+        .collect();
+      handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(keys.len(), THREADS * PER_THREAD);
+    assert_eq!(pool.allocated(), (THREADS * PER_THREAD) as u64);
+    let lowers: HashSet<u64> = keys.iter().map(|k| k & INTER_KEY_MASK).collect();
+    assert_eq!(lowers.len(), keys.len());
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn collision_skipped() {
+    use crate::keys_distinct;
+    use super::KeyPool;
+
+    let root = key(28);
+    let candidate = |c| key(u64(root, c));
+
+    // occupy the first candidate's lower nibbles with a different key
+    let pool = KeyPool::new(root);
+    let clash = Key::unchecked(candidate(0).inner() ^ (0x1 << 60));
+    assert!(!keys_distinct(clash, candidate(0)));
+    assert!(pool.reserve(clash));
+    assert!(!pool.reserve(candidate(0)));
+
+    let k = pool.allocate().unwrap();
+    assert_eq!(k.inner(), candidate(1).inner());
+    assert_eq!(pool.allocated(), 1);
+    assert_eq!(pool.allocate().unwrap().inner(), candidate(2).inner());
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn exhausted_pool_gives_up() {
+    use super::KeyPool;
+
+    // a root of 0 makes every candidate key(0)
+    let pool = KeyPool::new(Key::unchecked(0));
+    assert_eq!(pool.allocate().unwrap().inner(), key(0).inner());
+    assert!(pool.allocate().is_none());
+    assert_eq!(pool.allocated(), 1);
+
+    let mut fixed = FixedKeyPool::<4>::new(Key::unchecked(0));
+    assert!(fixed.allocate().is_some());
+    assert!(fixed.allocate().is_none());
+  }
+
+  #[cfg(all(feature = "critical-section", feature = "std"))]
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn shared_pool_from_threads() {
+    use std::{collections::HashSet, vec::Vec};
+    use crate::INTER_KEY_MASK;
+    use super::SharedKeyPool;
+
+    static POOL: SharedKeyPool<400> = SharedKeyPool::new(key(29));
+    assert!(POOL.reserve(key(1)));
+    let keys: Vec<u64> = std::thread::scope(|s| {
+      let handles: Vec<_> = (0..4)
+        .map(|_| s.spawn(|| (0..100).map_while(|_| POOL.allocate()).map(|k| k.inner()).collect::<Vec<_>>()))
+        .collect();
+      handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+
+    // the reserved key took one of the 400 slots
+    assert_eq!(keys.len(), 399);
+    assert_eq!(POOL.allocated(), 399);
+    assert!(POOL.allocate().is_none());
+    let lowers: HashSet<u64> = keys.iter().chain([&key(1).inner()]).map(|k| k & INTER_KEY_MASK).collect();
+    assert_eq!(lowers.len(), 400);
+  }
+
+  #[test]
+  fn fixed_pool_fills() {
+    let root = key(28);
+    let mut pool = FixedKeyPool::<3>::new(root);
+    let clash = Key::unchecked(key(u64(root, 0)).inner() ^ (0x1 << 60));
+    assert!(pool.reserve(clash));
+    assert!(!pool.reserve(clash));
+
+    // the same candidates as KeyPool: 0 is taken by the clash
+    assert_eq!(pool.allocate().unwrap().inner(), key(u64(root, 1)).inner());
+    assert_eq!(pool.allocate().unwrap().inner(), key(u64(root, 2)).inner());
+    assert_eq!(pool.allocated(), 2);
+    assert!(pool.allocate().is_none());
+    assert!(!pool.reserve(key(1)));
+  }
+}
@@ -0,0 +1,146 @@
+use crate::{Key, INTER_KEY_MASK};
+
+/// Why a key was refused by [`KeyTable::insert`].
+#[derive(Clone, Copy, Debug)]
+pub enum KeyCollision {
+  /// The key shares its lower 9 nibbles with this key already in the table.
+  Existing(Key),
+  /// Every slot is taken.
+  TableFull,
+}
+
+/// A fixed-capacity set of keys that enforces the inter-key rule (rule 5 of [`Key`]) on insert.
+///
+/// Open addressing over `N` slots, keyed by the lower 36 bits, so inserts and lookups
+/// take O(1) expected probes. Keep `N` around twice the number of keys you expect to hold;
+/// probing degrades as the table approaches full.
+#[derive(Clone, Debug)]
+pub struct KeyTable<const N: usize> {
+  slots: [Option<Key>; N],
+  len: usize,
+}
+
+impl<const N: usize> KeyTable<N> {
+  /// An empty table
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { slots: [None; N], len: 0 }
+  }
+
+  /// Number of keys held
+  #[must_use]
+  pub const fn len(&self) -> usize { self.len }
+
+  #[must_use]
+  pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+  /// The slot probing for `k` starts at
+  const fn home(k: Key) -> usize {
+    let h = (k.inner() & INTER_KEY_MASK).wrapping_mul(0x9e3779b97f4a7c15);
+    ((h as u128 * N as u128) >> 64) as usize
+  }
+
+  /// Finds the key conflicting with `k`, or else the first free slot on its probe path.
+  fn probe(&self, k: Key) -> Result<Key, Option<usize>> {
+    let mut slot = Self::home(k);
+    for _ in 0..N {
+      match self.slots[slot] {
+        None => return Err(Some(slot)),
+        Some(e) if (e.inner() ^ k.inner()) & INTER_KEY_MASK == 0 => return Ok(e),
+        Some(_) => slot = if slot + 1 == N { 0 } else { slot + 1 },
+      }
+    }
+    Err(None)
+  }
+
+  /// Returns the key in the table that `k` would collide with, if any.
+  #[must_use]
+  pub fn contains_conflict(&self, k: Key) -> Option<Key> {
+    self.probe(k).ok()
+  }
+
+  /// Adds `k`, unless it shares its lower 9 nibbles with a key already held (including `k` itself),
+  /// or the table is full.
+  pub fn insert(&mut self, k: Key) -> Result<(), KeyCollision> {
+    match self.probe(k) {
+      Ok(e) => Err(KeyCollision::Existing(e)),
+      Err(None) => Err(KeyCollision::TableFull),
+      Err(Some(slot)) => {
+        self.slots[slot] = Some(k);
+        self.len += 1;
+        Ok(())
+      }
+    }
+  }
+
+  /// The keys held, in slot order.
+  pub fn iter(&self) -> impl Iterator<Item = Key> + '_ {
+    self.slots.iter().filter_map(|&s| s)
+  }
+}
+
+impl<const N: usize> Default for KeyTable<N> {
+  fn default() -> Self { Self::new() }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, key_set, Key};
+  use super::{KeyCollision, KeyTable};
+
+  #[test]
+  fn rejects_colliding_key() {
+    let mut table = KeyTable::<8>::new();
+    let a = key(0);
+    // same lower 9 nibbles, different upper
+    let b = Key::unchecked(a.inner() ^ (0x3 << 40));
+    table.insert(a).unwrap();
+
+    match table.insert(b) {
+      Err(KeyCollision::Existing(e)) => assert_eq!(e.inner(), a.inner()),
+      r => panic!("{:?}", r),
+    }
+    assert!(matches!(table.insert(a), Err(KeyCollision::Existing(_))));
+    assert_eq!(table.contains_conflict(b).map(Key::inner), Some(a.inner()));
+    assert!(table.contains_conflict(key(1)).is_none());
+    assert_eq!(table.len(), 1);
+  }
+
+  #[test]
+  fn fixed_capacity() {
+    let mut set = [key(0); 4];
+    key_set(0, &mut set);
+
+    let mut table = KeyTable::<3>::new();
+    for k in &set[..3] {
+      table.insert(*k).unwrap();
+    }
+    assert!(matches!(table.insert(set[3]), Err(KeyCollision::TableFull)));
+    // a full table still reports conflicts
+    assert!(matches!(table.insert(set[1]), Err(KeyCollision::Existing(_))));
+    assert_eq!(table.len(), 3);
+
+    let mut none = KeyTable::<0>::new();
+    assert!(matches!(none.insert(set[0]), Err(KeyCollision::TableFull)));
+    assert!(none.is_empty());
+  }
+
+  #[test]
+  fn bulk_key_set() {
+    let n = if cfg!(miri) { 50 } else { 1_000 };
+    let mut set = std::vec![key(0); n];
+    key_set(123, &mut set);
+
+    let mut table = KeyTable::<2048>::new();
+    for k in &set {
+      table.insert(*k).unwrap();
+    }
+    assert_eq!(table.len(), n);
+
+    let mut held: std::vec::Vec<u64> = table.iter().map(Key::inner).collect();
+    let mut expected: std::vec::Vec<u64> = set.iter().map(|k| k.inner()).collect();
+    held.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(held, expected);
+  }
+}
@@ -1,9 +1,43 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
-#![allow(unused_imports)]
 
+#[cfg(any(test, feature = "std"))] extern crate std;
+#[cfg(feature = "alloc")] extern crate alloc;
+
+mod fold; pub use fold::fold_u64;
 mod key; pub use key::*;
+mod key_table; pub use key_table::*;
+mod key_pool; pub use key_pool::*;
+mod record; pub use record::*;
+mod lint; pub use lint::*;
+pub mod primitives;
 mod squares; pub use squares::*;
+mod sample; pub use sample::*;
+mod range; pub use range::*;
+mod index; pub use index::*;
+mod seq; pub use seq::*;
+mod dist; pub use dist::*;
+mod brownian; pub use brownian::*;
+mod privacy; pub use privacy::*;
+mod id; pub use id::*;
+mod attribute; pub use attribute::*;
+mod text; pub use text::*;
+mod limbs; pub use limbs::*;
+mod graph; pub use graph::*;
+mod color; pub use color::*;
+mod bundle; pub use bundle::*;
+mod hasher; pub use hasher::*;
+#[cfg(feature = "alloc")] mod vecs;
+#[cfg(feature = "alloc")] pub use vecs::*;
+#[cfg(feature = "std")] mod dev;
+#[cfg(feature = "std")] pub use dev::*;
+
+#[cfg(feature = "analysis")] pub mod analysis;
+#[cfg(feature = "wasm-bindings")] pub mod wasm;
+#[cfg(feature = "getrandom-backend")] mod getrandom_backend;
+#[cfg(feature = "getrandom-backend")] pub use getrandom_backend::*;
 
-#[cfg(feature = "rand")] mod rand;
-#[cfg(feature = "rand")] pub use rand::*;
\ No newline at end of file
+#[cfg(any(feature = "rand", feature = "rand_09"))] mod rand;
+#[cfg(any(feature = "rand", feature = "rand_09"))] pub use rand::*;
+#[cfg(any(feature = "rand", feature = "rand_09"))] mod replay;
+#[cfg(any(feature = "rand", feature = "rand_09"))] pub use replay::*;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use crate::{derive, u64, Key};
+
+/// Fills the low `bits` bits of `limbs` (little-endian `u64` limbs) from
+/// `u64(key, start_index + j)` for limb `j`, and zeroes the rest.
+fn fill_low_bits(key: Key, start_index: u64, bits: u64, limbs: &mut [u64]) {
+  let used = bits.div_ceil(64) as usize;
+  assert!(limbs.len() >= used, "limb buffer too short for {} bits", bits);
+
+  for (j, limb) in limbs.iter_mut().enumerate() {
+    *limb = if j < used { u64(key, start_index.wrapping_add(j as u64)) } else { 0 };
+  }
+  if !bits.is_multiple_of(64) {
+    limbs[used - 1] &= (1 << (bits % 64)) - 1;
+  }
+}
+
+/// Writes a uniformly random integer of exactly `bits` bits (the top one set) into `limbs`,
+/// as little-endian `u64` limbs; limbs above the width are zeroed.
+///
+/// Limb `j` comes from `u64(key, index + j)`, so this consumes `bits.div_ceil(64)` counters.
+///
+/// ## Panics
+/// If `bits == 0`, or `limbs` has fewer than `bits.div_ceil(64)` limbs.
+pub fn random_bits(key: Key, index: u64, bits: u64, limbs: &mut [u64]) {
+  assert!(bits != 0, "random_bits: bits must be non-zero");
+  fill_low_bits(key, index, bits, limbs);
+  limbs[((bits - 1) / 64) as usize] |= 1 << ((bits - 1) % 64);
+}
+
+/// the bit length of a little-endian limb integer
+fn bit_length(limbs: &[u64]) -> u64 {
+  match limbs.iter().rposition(|&l| l != 0) {
+    Some(top) => top as u64 * 64 + 64 - limbs[top].leading_zeros() as u64,
+    None => 0,
+  }
+}
+
+/// `a < b` for little-endian limb integers, where `b` may be shorter
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+  for j in (0..a.len().max(b.len())).rev() {
+    let (x, y) = (a.get(j).copied().unwrap_or(0), b.get(j).copied().unwrap_or(0));
+    if x != y { return x < y; }
+  }
+  false
+}
+
+/// Writes a uniformly random integer in `0..modulus` into `out`, both little-endian `u64` limbs;
+/// limbs of `out` above the modulus' width are zeroed.
+///
+/// Draws integers as wide as `modulus` (as [`random_bits`] does, without forcing the top bit)
+/// and rejects those not below it, fewer than 2 attempts on average.
+/// With `n` the modulus' limb count, attempt `t` takes limb `j` from counter `derive(index, t * n) + j`,
+/// where `derive(index, 0) = index` and otherwise `derive(index, k) = u64(DERIVE_KEY, index) + k`,
+/// so attempts never share a counter.
+///
+/// ## Panics
+/// If `modulus` is zero, or `out` has fewer limbs than the modulus needs.
+pub fn random_below(key: Key, index: u64, modulus: &[u64], out: &mut [u64]) {
+  let bits = bit_length(modulus);
+  assert!(bits != 0, "random_below: modulus must be non-zero");
+
+  let stride = bits.div_ceil(64);
+  let mut attempt = 0;
+  loop {
+    fill_low_bits(key, derive(index, attempt * stride), bits, out);
+    if less_than(out, modulus) { return; }
+    attempt += 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u64};
+  use super::{bit_length, random_below, random_bits};
+
+  #[test]
+  fn random_bits_exact_width() {
+    let k = key(22);
+    for bits in [1, 2, 63, 64, 65, 127, 128, 1024] {
+      for i in 0..50 {
+        let mut limbs = [u64::MAX; 17];
+        random_bits(k, i * 20, bits, &mut limbs);
+        assert_eq!(bit_length(&limbs), bits, "{:?}", limbs);
+      }
+    }
+
+    // the draws themselves, masked, with the top bit forced
+    let mut limbs = [0; 2];
+    random_bits(k, 7, 65, &mut limbs);
+    assert_eq!(limbs, [u64(k, 7), 1]);
+    random_bits(k, 7, 63, &mut limbs);
+    assert_eq!(limbs, [(u64(k, 7) & !(1 << 63)) | 1 << 62, 0]);
+
+    let caught = std::panic::catch_unwind(|| random_bits(k, 0, 129, &mut [0; 2]));
+    assert!(caught.is_err());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn random_below_small_moduli_exhaustive() {
+    let k = key(23);
+    for m in 1..=40u64 {
+      let mut counts = [0u32; 40];
+      for i in 0..(m * 200) {
+        let mut out = [0; 2];
+        random_below(k, i << 20, &[m], &mut out);
+        assert!(out[0] < m && out[1] == 0, "{} {:?}", m, out);
+        counts[out[0] as usize] += 1;
+      }
+      // each value expected 200 times
+      assert!(counts[..m as usize].iter().all(|&c| (120..290).contains(&c)), "{} {:?}", m, counts);
+    }
+  }
+
+  #[test]
+  fn random_below_wide_modulus() {
+    let k = key(24);
+    // 2^64 + 2: values are [x, 0] for any x, or [0 | 1, 1]
+    let modulus = [2, 1];
+    let mut high = 0;
+    for i in 0..2_000 {
+      let mut out = [0; 3];
+      random_below(k, i, &modulus, &mut out);
+      assert!(out[1] == 0 || (out[1] == 1 && out[0] < 2), "{:?}", out);
+      assert_eq!(out[2], 0);
+      high += out[1];
+    }
+    // values >= 2^64 are about 2^-63 of the range
+    assert_eq!(high, 0);
+
+    let mut one = [9];
+    random_below(k, 0, &[1, 0, 0], &mut one);
+    assert_eq!(one, [0]);
+  }
+}
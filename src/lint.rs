@@ -0,0 +1,155 @@
+//! Advisory key lints.
+//!
+//! These flag admissible keys with suspicious structure (e.g. a key that looks hand-typed).
+//! They are *not* part of admissibility: a linted key still produces valid output,
+//! and keys from [`key`](crate::key) occasionally trip them by chance.
+
+use crate::Key;
+
+/// A structural warning about an admissible key.
+///
+/// More lints may be added, so matches need a wildcard arm:
+///
+/// ```compile_fail
+/// fn code(lint: squares::KeyLint) -> u8 {
+///   match lint {
+///     squares::KeyLint::RepeatedUpperRun => 0,
+///     squares::KeyLint::MonotoneLower => 1,
+///     squares::KeyLint::LowDiversity => 2,
+///   }
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyLint {
+  /// 5 or more consecutive identical nibbles among the upper 7.
+  RepeatedUpperRun,
+  /// 7 or more consecutive lower nibbles strictly ascending or descending.
+  MonotoneLower,
+  /// The key uses only 8 distinct nibble values, the fewest admissibility allows.
+  LowDiversity,
+}
+
+impl KeyLint {
+  /// every lint, in bit order
+  const ALL: [KeyLint; 3] = [KeyLint::RepeatedUpperRun, KeyLint::MonotoneLower, KeyLint::LowDiversity];
+
+  const fn bit(self) -> u8 {
+    match self {
+      KeyLint::RepeatedUpperRun => 1 << 0,
+      KeyLint::MonotoneLower => 1 << 1,
+      KeyLint::LowDiversity => 1 << 2,
+    }
+  }
+
+  /// A short explanation suitable for showing an operator.
+  pub const fn explanation(&self) -> &'static str {
+    match self {
+      KeyLint::RepeatedUpperRun => "Upper nibbles contain a long run of one value",
+      KeyLint::MonotoneLower => "Lower nibbles form a long ascending or descending run",
+      KeyLint::LowDiversity => "Key uses very few distinct nibble values",
+    }
+  }
+}
+
+/// The set of [`KeyLint`]s a key triggers (see [`lint`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyLints(u8);
+
+impl KeyLints {
+  #[must_use] #[inline(always)]
+  pub const fn is_empty(self) -> bool { self.0 == 0 }
+
+  #[must_use] #[inline(always)]
+  pub const fn contains(self, lint: KeyLint) -> bool { self.0 & lint.bit() != 0 }
+
+  /// The triggered lints, in declaration order.
+  pub fn iter(self) -> impl Iterator<Item = KeyLint> {
+    KeyLint::ALL.into_iter().filter(move |&l| self.contains(l))
+  }
+
+  const fn with(self, lint: KeyLint) -> Self { Self(self.0 | lint.bit()) }
+}
+
+/// the `i`-th nibble of `k`, counting from the least significant
+const fn nibble(k: u64, i: u32) -> u8 {
+  ((k >> (i * 4)) & 0xF) as u8
+}
+
+/// Checks an (admissible) key for structurally weak patterns. Advisory only, see [`KeyLint`].
+///
+/// Background rates over keys from [`key`](crate::key) are roughly:
+/// [`RepeatedUpperRun`](KeyLint::RepeatedUpperRun) 0.08%,
+/// [`MonotoneLower`](KeyLint::MonotoneLower) 0.1%,
+/// and never [`LowDiversity`](KeyLint::LowDiversity) (its 9th nibble is always a new value).
+#[must_use]
+pub const fn lint(key: Key) -> KeyLints {
+  let k = key.inner();
+  let mut lints = KeyLints(0);
+
+  // upper 7 nibbles are positions 9..16
+  let mut run = 1;
+  let mut i = 10;
+  while i < 16 {
+    run = if nibble(k, i) == nibble(k, i - 1) { run + 1 } else { 1 };
+    if run >= 5 { lints = lints.with(KeyLint::RepeatedUpperRun); }
+    i += 1;
+  }
+
+  let mut up = 1;
+  let mut down = 1;
+  let mut i = 1;
+  while i < 8 {
+    up = if nibble(k, i) > nibble(k, i - 1) { up + 1 } else { 1 };
+    down = if nibble(k, i) < nibble(k, i - 1) { down + 1 } else { 1 };
+    if up >= 7 || down >= 7 { lints = lints.with(KeyLint::MonotoneLower); }
+    i += 1;
+  }
+
+  let mut seen = 0u16;
+  let mut i = 0;
+  while i < 16 {
+    seen |= 1 << nibble(k, i);
+    i += 1;
+  }
+  if seen.count_ones() <= 8 { lints = lints.with(KeyLint::LowDiversity); }
+
+  lints
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, Key};
+  use super::{lint, KeyLint};
+
+  #[test]
+  fn crafted_keys() {
+    let counting = Key::checked(0xaaaaaaa9_87654321).unwrap();
+    let lints = lint(counting);
+    assert!(lints.contains(KeyLint::RepeatedUpperRun) && lints.contains(KeyLint::MonotoneLower));
+    assert!(!lints.contains(KeyLint::LowDiversity));
+    assert_eq!(lints.iter().collect::<std::vec::Vec<_>>(), [KeyLint::RepeatedUpperRun, KeyLint::MonotoneLower]);
+
+    let narrow = Key::checked(0x87654312_18273645).unwrap();
+    assert_eq!(lint(narrow).iter().collect::<std::vec::Vec<_>>(), [KeyLint::LowDiversity]);
+
+    for l in lints.iter() {
+      assert!(!l.explanation().is_empty());
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn background_rate() {
+    let n = 100_000;
+    let mut counts = [0; 3];
+    for i in 0..n {
+      let lints = lint(key(i));
+      for (c, l) in counts.iter_mut().zip([KeyLint::RepeatedUpperRun, KeyLint::MonotoneLower, KeyLint::LowDiversity]) {
+        *c += lints.contains(l) as u64;
+      }
+    }
+    // documented: 0.08%, 0.1%, 0
+    assert!(counts[0] < n / 500 && counts[1] < n / 500 && counts[2] == 0, "{:?}", counts);
+  }
+}
@@ -0,0 +1,69 @@
+//! # Primitives
+//!
+//! The building blocks every Squares output is made of, for experimenting with variants
+//! (round counts, output taps). These are building blocks, not a stability-guaranteed stream:
+//! only the output functions such as [`u32()`](crate::u32) and [`u64()`](crate::u64) are frozen by
+//! the known-answer vectors.
+//!
+//! [`u32()`](crate::u32), rebuilt from them:
+//!
+//! ```
+//! use squares::{key, primitives::{init, round, sq}};
+//!
+//! let (k, counter) = (key(0), 5);
+//! let (x, y, z) = init(k, counter);
+//! let x = round(round(round(x, y), z), y);
+//! assert_eq!((sq(x, z) >> 32) as u32, squares::u32(k, counter));
+//! ```
+
+use crate::Key;
+
+/// Exchanges the `u32` halves of a `u64`.
+#[must_use] #[inline(always)]
+pub const fn swap(x: u64) -> u64 {
+  x.rotate_left(32)
+}
+
+/// `x * x + o`, wrapping.
+#[must_use] #[inline(always)]
+pub const fn sq(x: u64, o: u64) -> u64 {
+  x.wrapping_mul(x).wrapping_add(o)
+}
+
+/// One round of Squares: [`sq`] then [`swap`].
+#[must_use] #[inline(always)]
+pub const fn round(x: u64, o: u64) -> u64 {
+  swap(sq(x, o))
+}
+
+/// The initial step of all Squares variants: `(x, y, z)` with `x = y = counter * key` and `z = y + key`.
+#[must_use] #[inline(always)]
+pub const fn init(key: Key, counter: u64) -> (u64, u64, u64) {
+  let k = key.inner();
+  let x = counter.wrapping_mul(k);
+  let z = x.wrapping_add(k);
+  (x, x, z)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, Key};
+  use super::{init, round, sq, swap};
+
+  #[test]
+  fn primitives_rebuild_outputs() {
+    let keys = [key(0), key(1), key(u64::MAX), Key::unchecked(0xaf9ed4c87b8e4fa5)];
+    for k in keys {
+      for counter in [0, 1, 2, 0xffff_ffff, 1 << 32, 1 << 63, u64::MAX] {
+        let (x, y, z) = init(k, counter);
+        let x2 = round(round(x, y), z);
+        let x3 = round(x2, y);
+        assert_eq!((sq(x3, z) >> 32) as u32, crate::u32(k, counter));
+        assert_eq!((sq(x2, y) >> 32) as u32, crate::u32_fast(k, counter));
+
+        let t = sq(x3, z);
+        assert_eq!(t ^ (sq(swap(t), y) >> 32), crate::u64(k, counter));
+      }
+    }
+  }
+}
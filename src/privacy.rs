@@ -0,0 +1,78 @@
+//! Differential-privacy noise, keyed so that noisy releases can be reproduced in an audit.
+//!
+//! Reproducibility and the formal guarantee pull against each other: the bounds of differential
+//! privacy assume the noise is unknown to the adversary, but anyone holding the key can recompute
+//! it and subtract it out. Keep the key secret, use a fresh key per release (reusing one makes
+//! the noise of two releases identical, so it cancels from their difference), and never derive it from
+//! the data or from public values like a date.
+
+use crate::{f64, f64_open, Key};
+
+/// Laplace(0, `scale`) noise, e.g. `scale = sensitivity / epsilon` for the Laplace mechanism:
+/// `-scale · sign(u) · ln(1 - 2|u|)` with `u = f64_open(key, index) - 1/2`, never `0` on `u`.
+///
+/// See the [module docs](self) on keeping the key secret.
+///
+/// ## Panics
+/// If `scale` is not positive and finite.
+#[must_use]
+pub fn laplace(key: Key, index: u64, scale: f64) -> f64 {
+  assert!(scale > 0.0 && scale.is_finite(), "laplace: scale must be positive and finite");
+  let u = f64_open(key, index) - 0.5;
+  libm::copysign(-scale * libm::log1p(-2.0 * libm::fabs(u)), u)
+}
+
+/// Randomized response: reports `truth` with probability `p_truth` (when `f64(key, index) < p_truth`)
+/// and its negation otherwise, which is `ln(p_truth / (1 - p_truth))`-differentially private for `p_truth >= 1/2`.
+///
+/// See the [module docs](self) on keeping the key secret.
+///
+/// ## Panics
+/// If `p_truth` is not in `[0, 1]`.
+#[must_use]
+pub fn randomized_response(key: Key, index: u64, truth: bool, p_truth: f64) -> bool {
+  assert!((0.0..=1.0).contains(&p_truth), "randomized_response: p_truth must be in [0, 1]");
+  if f64(key, index) < p_truth { truth } else { !truth }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use super::{laplace, randomized_response};
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn laplace_scale_and_symmetry() {
+    let n = 40_000;
+    for scale in [0.5, 3.0] {
+      let xs: std::vec::Vec<f64> = (0..n).map(|i| laplace(key(43), i, scale)).collect();
+      // E|X| = scale, Var|X| = scale^2: 5 standard errors is 2.5% of the scale
+      let mean_abs = xs.iter().map(|x| x.abs()).sum::<f64>() / n as f64;
+      assert!((mean_abs / scale - 1.0).abs() < 0.025, "{} {}", scale, mean_abs);
+      let mean = xs.iter().sum::<f64>() / n as f64;
+      assert!(mean.abs() / scale < 0.035, "{} {}", scale, mean);
+      let positive = xs.iter().filter(|&&x| x > 0.0).count() as f64 / n as f64;
+      assert!((positive - 0.5).abs() < 0.0125, "{}", positive);
+      assert!(xs.iter().all(|x| x.is_finite()));
+      // increasing in the uniform
+      assert_eq!(xs[0] > 0.0, crate::f64_open(key(43), 0) > 0.5);
+    }
+    assert!(std::panic::catch_unwind(|| laplace(key(43), 0, 0.0)).is_err());
+    assert!(std::panic::catch_unwind(|| laplace(key(43), 0, -1.0)).is_err());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn randomized_response_flip_rate() {
+    let n = 40_000;
+    for p in [0.5, 0.75, 0.9] {
+      for truth in [false, true] {
+        let flips = (0..n).filter(|&i| randomized_response(key(44), i, truth, p) != truth).count();
+        // 5 standard deviations of the binomial is at most 0.0125
+        assert!((flips as f64 / n as f64 - (1.0 - p)).abs() < 0.0125, "{} {} {}", p, truth, flips);
+      }
+    }
+    assert!((0..100).all(|i| randomized_response(key(44), i, true, 1.0)));
+    assert!((0..100).all(|i| !randomized_response(key(44), i, true, 0.0)));
+  }
+}
@@ -1,59 +1,642 @@
-#![allow(unused)]
+use crate::{key, key_from_bytes, key_set, Inadmissible, Key};
 
-use rand_core::{impls::fill_bytes_via_next, RngCore};
-use crate::Key;
+/// Distance of the conventional "jump to a fresh region" (see [`Squares::jump`])
+pub const JUMP_DEFAULT: u64 = 1 << 32;
 
 /// An RNG compatible with `rand`.
 /// Increments counter internally.
+///
+/// As a byte source it is a plain stream: `u64(key, index)` little-endian, word after word.
+/// A `fill_bytes` ending mid-word leaves the rest of that word for the next `fill_bytes`,
+/// while `next_u32`/`next_u64` always start on a fresh word, discarding any partial one.
+///
+/// Implements `RngCore` from `rand_core` 0.6 (feature `rand`)
+/// and/or `rand_core` 0.9 (feature `rand_09`).
 #[derive(Clone, Copy, Debug)]
-struct Squares {
+pub struct Squares {
   key: Key,
-  index: u64
+  index: u64,
+  /// bytes of word `index` already handed out by `fill_bytes`, in `0..8`
+  offset: u8
 }
 
 impl Squares {
   #[must_use] #[inline(always)]
   pub const fn with_key(key: Key) -> Self {
-    Self { key, index: 0 }
+    Self { key, index: 0, offset: 0 }
   }
 
-  /// Set the location in the RNG sequence
+  /// Like [`with_key`](Self::with_key), but re-checks the key's value, rejecting an inadmissible
+  /// one that was wrapped with [`Key::unchecked`].
+  pub const fn with_key_strict(key: Key) -> Result<Self, Inadmissible> {
+    match Key::checked(key.inner()) {
+      Ok(key) => Ok(Self::with_key(key)),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Seeds a generator from a name, e.g. a world name.
+  /// The UTF-8 bytes derive the key through [`key_from_bytes`], and the index starts at 0.
+  #[must_use] #[inline(always)]
+  pub const fn from_name(name: &str) -> Self {
+    Self::with_key(key_from_bytes(name.as_bytes()))
+  }
+
+  /// Seeds a generator from the system clock and process id, for throwaway scripts and tests.
+  ///
+  /// ## Warning:
+  /// This is *convenience-grade* seeding. The seed is guessable from when (and as which process)
+  /// the generator was made, so never use it where outputs must be unpredictable.
+  ///
+  /// The nanoseconds since the Unix epoch (or before it, if the clock is set earlier),
+  /// the process id, and a per-process call counter are folded into an index for [`key`], so the key
+  /// is always admissible. The counter makes every call's seed material unique, but neither the fold
+  /// nor [`key`] is proven injective: keys from two calls are distinct with overwhelming probability.
+  #[cfg(feature = "std")]
+  #[must_use]
+  pub fn from_time() -> Self {
+    use crate::fold::fold_step;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+      Ok(d) => d.as_nanos(),
+      Err(e) => !e.duration().as_nanos(),
+    };
+    let mut seed = fold_step(nanos as u64, (nanos >> 64) as u64);
+    seed = fold_step(seed, std::process::id() as u64);
+    seed = fold_step(seed, CALLS.fetch_add(1, Ordering::Relaxed) as u64);
+    Self::with_key(key(seed))
+  }
+
+  /// `D` generators for `D` independent dimensions of a simulation, each at index 0
+  /// under its own key: `key(base_index + d)`, as minted by [`key_set`].
+  ///
+  /// The keys are admissible and pairwise distinct under the inter-key rule;
+  /// in the rare case two would collide, [`key_set`] skips to the next index.
+  #[must_use]
+  pub fn independent_streams<const D: usize>(base_index: u64) -> [Squares; D] {
+    let mut keys = [key(0); D];
+    key_set(base_index, &mut keys);
+    keys.map(Squares::with_key)
+  }
+
+  /// Set the location in the RNG sequence (at the start of word `index`)
   #[must_use] #[inline(always)]
   pub const fn with_index(self, index: u64) -> Self {
-    Self { key: self.key, index }
+    Self { key: self.key, index, offset: 0 }
   }
 
   /// Get the current location in the RNG sequence
   #[must_use] #[inline(always)]
   pub const fn index(&self) -> u64 { self.index }
 
-  /// Skip ahead in the sequence
+  /// Get the key, which with [`index`](Self::index) identifies the generator: log both, and
+  /// `Squares::with_key(key).with_index(index)` reproduces the stream from that word on.
+  #[must_use] #[inline(always)]
+  pub const fn key(&self) -> Key { self.key }
+
+  /// `SeedableRng`'s seed mapping: key index, then counter, little-endian
+  const fn from_seed_bytes(seed: [u8; 16]) -> Self {
+    let [k0, k1, k2, k3, k4, k5, k6, k7, i0, i1, i2, i3, i4, i5, i6, i7] = seed;
+    let key_index = u64::from_le_bytes([k0, k1, k2, k3, k4, k5, k6, k7]);
+    Self::with_key(key(key_index)).with_index(u64::from_le_bytes([i0, i1, i2, i3, i4, i5, i6, i7]))
+  }
+
+  /// Skip ahead `n` words in the sequence (wrapping around at the end of the index space).
+  /// A partially read word stays partially read.
   #[must_use] #[inline(always)]
   pub const fn skip(mut self, n: u64) -> Self {
-    self.index += n;
+    self.index = self.index.wrapping_add(n);
     self
   }
-}
 
-impl RngCore for Squares {
+  /// Jump ahead `distance` outputs.
+  /// Squares is counter-based, so this is just an addition to the index.
+  /// Use [`JUMP_DEFAULT`] to move to a fresh region, as with the xoshiro family's `jump`.
+  #[inline(always)]
+  pub fn jump(&mut self, distance: u64) {
+    self.index = self.index.wrapping_add(distance);
+  }
+
+  /// Skip ahead `n` bytes of the byte stream read by `fill_bytes`.
+  #[inline(always)]
+  pub fn skip_bytes(&mut self, n: u64) {
+    let offset = self.offset as u64 + n % 8;
+    self.index = self.index.wrapping_add(n / 8 + offset / 8);
+    self.offset = (offset % 8) as u8;
+  }
+
+  /// Moves to the start of the next word, if the current one was partially read
+  #[inline(always)]
+  fn align(&mut self) {
+    if self.offset != 0 {
+      self.index = self.index.wrapping_add(1);
+      self.offset = 0;
+    }
+  }
+
+  /// Replaces the key and rewinds to index 0, as if freshly constructed with [`Squares::with_key`].
+  #[inline(always)]
+  pub fn reset_to(&mut self, key: Key) {
+    *self = Self::with_key(key);
+  }
+
+  #[inline(always)]
   fn next_u32(&mut self) -> u32 {
+    self.align();
     let r = super::u32(self.key, self.index);
-    self.index += 1;
+    self.index = self.index.wrapping_add(1);
     r
   }
 
+  #[inline(always)]
   fn next_u64(&mut self) -> u64 {
+    self.align();
     let r = super::u64(self.key, self.index);
-    self.index += 1;
+    self.index = self.index.wrapping_add(1);
     r
   }
 
+  /// A uniform `u64` in `0..n` and the number of draws rejected on the way, for profiling
+  /// rejection rates. Draws words from the stream by widening multiply with rejection,
+  /// as `SquaresRngExt::gen_bounded` does, giving the same value.
+  ///
+  /// Rejections happen with probability `(2^64 mod n) / 2^64` per draw, so never for powers of two.
+  ///
+  /// ## Panics
+  /// If `n == 0`.
+  #[inline]
+  pub fn range_u64_counted(&mut self, n: u64) -> (u64, u32) {
+    assert!(n != 0, "range_u64_counted: n must be non-zero");
+    let threshold = n.wrapping_neg() % n;
+    let mut rejections = 0;
+    loop {
+      let m = self.next_u64() as u128 * n as u128;
+      if m as u64 >= threshold {
+        return ((m >> 64) as u64, rejections);
+      }
+      rejections += 1;
+    }
+  }
+
+  /// Shuffles `slice` in place exactly as [`shuffle`](crate::shuffle)`(key, index, slice)` would at the
+  /// current index, then advances past the `slice.len() - 1` indices that consumed.
+  /// Like `next_u64`, starts on a fresh word.
+  #[inline]
+  pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+    self.align();
+    super::shuffle(self.key, self.index, slice);
+    self.index = self.index.wrapping_add(slice.len().saturating_sub(1) as u64);
+  }
+
+  /// Continues the byte stream: the rest of a partially read word, then [`fill_bytes`](crate::fill_bytes).
+  #[inline(always)]
   fn fill_bytes(&mut self, dest: &mut [u8]) {
-    fill_bytes_via_next(self, dest);
+    let mut dest = dest;
+    if self.offset != 0 {
+      let word = super::u64(self.key, self.index).to_le_bytes();
+      let n = (8 - self.offset as usize).min(dest.len());
+      let (head, rest) = dest.split_at_mut(n);
+      for (dst, src) in head.iter_mut().zip(word.iter().skip(self.offset as usize)) {
+        *dst = *src;
+      }
+      self.skip_bytes(n as u64);
+      dest = rest;
+    }
+
+    super::fill_bytes(self.key, self.index, dest);
+    self.skip_bytes(dest.len() as u64);
   }
+}
+
+#[cfg(feature = "rand")]
+impl rand_core::RngCore for Squares {
+  #[inline(always)]
+  fn next_u32(&mut self) -> u32 { Squares::next_u32(self) }
 
+  #[inline(always)]
+  fn next_u64(&mut self) -> u64 { Squares::next_u64(self) }
+
+  #[inline(always)]
+  fn fill_bytes(&mut self, dest: &mut [u8]) { Squares::fill_bytes(self, dest) }
+
+  #[inline(always)]
   fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-    fill_bytes_via_next(self, dest);
+    Squares::fill_bytes(self, dest);
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+/// Squares can never fail: drawing is pure arithmetic on the key and counter. Under `rand_core` 0.9
+/// it is an infallible `RngCore`, so it also gets `TryRngCore` with `Error = Infallible`
+/// (and the 0.6 `try_fill_bytes` always returns `Ok`).
+#[cfg(feature = "rand_09")]
+impl rand_core_09::RngCore for Squares {
+  #[inline(always)]
+  fn next_u32(&mut self) -> u32 { Squares::next_u32(self) }
+
+  #[inline(always)]
+  fn next_u64(&mut self) -> u64 { Squares::next_u64(self) }
+
+  #[inline(always)]
+  fn fill_bytes(&mut self, dest: &mut [u8]) { Squares::fill_bytes(self, dest) }
+}
+
+/// Seeds map to generators portably, on every version and platform: a 16-byte seed is two
+/// little-endian `u64`s, the first the index of the key ([`key`]`(seed[0..8])`, so any seed gives an
+/// admissible key) and the second the starting counter. `seed_from_u64(n)` is
+/// `Squares::with_key(key(n))`, identical to `from_seed` with `n` and a zero counter. So an 8-byte
+/// seed `s` is `from_seed` of `s` followed by eight zero bytes, or `seed_from_u64(u64::from_le_bytes(s))`.
+///
+/// ## Warning:
+/// As with [`key`], if the key half of the seed is a small number, an adversary may brute-force it
+/// from outputs and predict the whole stream; seed from entropy (e.g. `from_entropy`) where that matters.
+#[cfg(feature = "rand")]
+impl rand_core::SeedableRng for Squares {
+  type Seed = [u8; 16];
+
+  #[inline]
+  fn from_seed(seed: Self::Seed) -> Self { Self::from_seed_bytes(seed) }
+
+  #[inline]
+  fn seed_from_u64(state: u64) -> Self { Self::with_key(key(state)) }
+}
+
+/// The same seed mapping as under `rand_core` 0.6.
+#[cfg(feature = "rand_09")]
+impl rand_core_09::SeedableRng for Squares {
+  type Seed = [u8; 16];
+
+  #[inline]
+  fn from_seed(seed: Self::Seed) -> Self { Self::from_seed_bytes(seed) }
+
+  #[inline]
+  fn seed_from_u64(state: u64) -> Self { Self::with_key(key(state)) }
+}
+
+/// Squares-flavoured helpers on any `rand_core` 0.6 `RngCore` handle.
+///
+/// Every method is built on `next_u64` alone, which for [`Squares`] inlines to one
+/// [`u64()`](crate::u64) evaluation per draw, so these cost the same as the free functions.
+#[cfg(feature = "rand")]
+pub trait SquaresRngExt: rand_core::RngCore {
+  /// Two consecutive `u64`s.
+  #[inline(always)]
+  fn gen_u64_pair(&mut self) -> (u64, u64) {
+    let a = self.next_u64();
+    (a, self.next_u64())
+  }
+
+  /// A uniform `u64` in `0..n`, by widening multiply with rejection as [`bounded`](crate::bounded)
+  /// (rejections take further draws).
+  ///
+  /// ## Panics
+  /// If `n == 0`.
+  #[inline]
+  fn gen_bounded(&mut self, n: u64) -> u64 {
+    assert!(n != 0, "gen_bounded: n must be non-zero");
+    loop {
+      let m = self.next_u64() as u128 * n as u128;
+      let lo = m as u64;
+      if lo >= n || lo >= n.wrapping_neg() % n {
+        return (m >> 64) as u64;
+      }
+    }
+  }
+
+  /// A standard normal, by Box–Muller from two draws.
+  #[inline]
+  fn gen_normal(&mut self) -> f64 {
+    let scale = 1.0 / (1u64 << 53) as f64;
+    let u1 = ((self.next_u64() >> 11) as f64 + 0.5) * scale;
+    let u2 = (self.next_u64() >> 11) as f64 * scale;
+    crate::dist::normal_from(u1, u2)
+  }
+}
+
+#[cfg(feature = "rand")]
+impl<R: rand_core::RngCore + ?Sized> SquaresRngExt for R {}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, u32, u64};
+  use super::{Squares, JUMP_DEFAULT};
+
+  #[cfg(feature = "rand")]
+  #[test]
+  fn ext_methods_on_squares() {
+    use super::SquaresRngExt;
+
+    let k = key(9);
+    let mut rng = Squares::with_key(k);
+    assert_eq!(rng.gen_u64_pair(), (u64(k, 0), u64(k, 1)));
+    assert_eq!(rng.gen_bounded(10), crate::bounded(k, 2, 10));
+    assert!((0..1_000).all(|_| rng.gen_bounded(3) < 3));
+
+    let mut rng = Squares::with_key(k);
+    let n = if cfg!(miri) { 100 } else { 20_000 };
+    let mean = (0..n).map(|_| rng.gen_normal()).sum::<f64>() / n as f64;
+    assert!(cfg!(miri) || mean.abs() < 0.05, "{}", mean);
+    assert_eq!(rng.index, 2 * n);
+
+    // and through a trait object
+    let dynamic: &mut dyn rand_core::RngCore = &mut rng;
+    assert!(dynamic.gen_bounded(5) < 5);
+  }
+
+  #[cfg(feature = "rand")]
+  #[test]
+  fn seedable_mapping_frozen() {
+    use rand_core::SeedableRng;
+
+    let mut seed = [0; 16];
+    seed[..8].copy_from_slice(&7u64.to_le_bytes());
+    seed[8..].copy_from_slice(&1000u64.to_le_bytes());
+    let mut rng = Squares::from_seed(seed);
+    assert_eq!((rng.key().inner(), rng.index()), (key(7).inner(), 1000));
+    assert_eq!(rng.next_u64(), u64(key(7), 1000));
+
+    let mut rng = Squares::from_seed(core::array::from_fn(|i| i as u8));
+    assert_eq!([rng.next_u64(), rng.next_u64()], [0x74032c720ee1e1d9, 0xc860cb155e6b198d]);
+
+    let mut a = Squares::seed_from_u64(42);
+    let mut b = Squares::with_key(key(42));
+    assert_eq!(a.next_u64(), b.next_u64());
+    assert_eq!(Squares::seed_from_u64(42).next_u64(), Squares::from_seed([42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).next_u64());
+    assert_eq!(Squares::seed_from_u64(42).next_u64(), 0x94fe0ceafb9c1c07);
+
+    // an 8-byte seed is the key half with a zero counter
+    let short = 0x0123_4567_89ab_cdefu64.to_le_bytes();
+    let mut long = [0; 16];
+    long[..8].copy_from_slice(&short);
+    assert_eq!(Squares::from_seed(long).next_u64(), Squares::seed_from_u64(u64::from_le_bytes(short)).next_u64());
+  }
+
+  #[cfg(feature = "rand_09")]
+  #[test]
+  fn seedable_09_matches_06() {
+    use rand_core_09::{RngCore, SeedableRng};
+
+    let seed: [u8; 16] = core::array::from_fn(|i| (i * 17) as u8);
+    let (k, i) = (u64::from_le_bytes(seed[..8].try_into().unwrap()), u64::from_le_bytes(seed[8..].try_into().unwrap()));
+    assert_eq!(RngCore::next_u64(&mut Squares::from_seed(seed)), u64(key(k), i));
+    assert_eq!(Squares::seed_from_u64(42).key().inner(), key(42).inner());
+  }
+
+  #[cfg(feature = "rand_09")]
+  #[test]
+  fn rand_core_09_infallible() {
+    use core::convert::Infallible;
+    use rand_core_09::{TryRngCore, UnwrapErr};
+
+    fn infallible<R: TryRngCore<Error = Infallible>>(rng: &mut R) -> Result<u64, Infallible> {
+      let mut buf = [0; 13];
+      rng.try_fill_bytes(&mut buf)?;
+      rng.try_next_u32()?;
+      rng.try_next_u64()
+    }
+
+    let k = key(2);
+    let mut rng = Squares::with_key(k);
+    let Ok(v) = infallible(&mut rng);
+    assert_eq!(v, u64(k, 3));
+
+    // and the fallible adapter never trips
+    let mut wrapped = UnwrapErr(Squares::with_key(k));
+    assert_eq!(rand_core_09::RngCore::next_u64(&mut wrapped), u64(k, 0));
+  }
+
+  #[test]
+  fn with_key_strict_rejects_unchecked() {
+    use crate::{Inadmissible, Key};
+
+    assert_eq!(Squares::with_key_strict(key(3)).map(|r| r.key.inner()), Ok(key(3).inner()));
+    let zero = Squares::with_key_strict(Key::unchecked(0x1dfd8627d5f20481)).map(|r| r.key.inner());
+    assert_eq!(zero, Err(Inadmissible::ContainsZeroNibble { position: 3, nibble: 0 }));
+  }
+
+  #[cfg(feature = "rand")]
+  #[test]
+  fn rand_core_06_stream() {
+    use rand_core::RngCore;
+
+    let k = key(1);
+    let mut rng = Squares::with_key(k).with_index(10);
+    assert_eq!(RngCore::next_u64(&mut rng), u64(k, 10));
+    assert_eq!(RngCore::next_u32(&mut rng), u32(k, 11));
+
+    let mut bytes = [0u8; 16];
+    rng.try_fill_bytes(&mut bytes).unwrap();
+    assert_eq!(bytes[..8], u64(k, 12).to_le_bytes());
+    assert_eq!(bytes[8..], u64(k, 13).to_le_bytes());
+    assert_eq!(rng.index(), 14);
+  }
+
+  #[cfg(feature = "rand_09")]
+  #[test]
+  fn rand_core_09_stream() {
+    use rand_core_09::RngCore;
+
+    let k = key(1);
+    let mut rng = Squares::with_key(k).with_index(10);
+    assert_eq!(RngCore::next_u64(&mut rng), u64(k, 10));
+    assert_eq!(RngCore::next_u32(&mut rng), u32(k, 11));
+
+    let mut bytes = [0u8; 16];
+    RngCore::fill_bytes(&mut rng, &mut bytes);
+    assert_eq!(bytes[..8], u64(k, 12).to_le_bytes());
+    assert_eq!(bytes[8..], u64(k, 13).to_le_bytes());
+    assert_eq!(rng.index(), 14);
+  }
+
+  #[test]
+  fn shuffle_advances() {
+    let mut a = Squares::with_key(key(3)).with_index(40);
+    let mut b = a;
+    let mut x: [u32; 50] = core::array::from_fn(|i| i as u32);
+    let mut y = x;
+    a.shuffle(&mut x);
+    b.shuffle(&mut y);
+    assert_eq!(x, y);
+    assert_ne!(x, core::array::from_fn(|i| i as u32));
+    let mut sorted = x;
+    sorted.sort_unstable();
+    assert_eq!(sorted, core::array::from_fn(|i| i as u32));
+
+    let mut z: [u32; 50] = core::array::from_fn(|i| i as u32);
+    crate::shuffle(key(3), 40, &mut z);
+    assert_eq!(x, z);
+    assert_eq!(a.index(), 40 + 49);
+
+    // nothing to consume for empty and single-element slices
+    a.shuffle(&mut [0u8; 0]);
+    a.shuffle(&mut [0u8]);
+    assert_eq!(a.index(), 40 + 49);
+  }
+
+  #[test]
+  fn range_u64_counted_rejections() {
+    let mut rng = Squares::with_key(key(3));
+    for shift in [0, 1, 7, 40, 63] {
+      for _ in 0..200 {
+        let (v, rejected) = rng.range_u64_counted(1 << shift);
+        assert!(v < 1 << shift);
+        assert_eq!(rejected, 0);
+      }
+    }
+
+    // 2^63 + 1 rejects almost half the draws; each rejection costs one word
+    let n = (1 << 63) + 1;
+    let start = rng.index();
+    let mut total = 0;
+    for _ in 0..200 {
+      let (v, rejected) = rng.range_u64_counted(n);
+      assert!(v < n);
+      total += rejected as u64;
+    }
+    assert_eq!(rng.index() - start, 200 + total);
+    assert!((100..300).contains(&total), "{}", total);
+  }
+
+  #[test]
+  fn jump_advances_index() {
+    let mut rng = Squares::with_key(key(3)).with_index(5);
+    rng.jump(JUMP_DEFAULT);
+    assert_eq!(rng.index(), 5 + JUMP_DEFAULT);
+
+    rng.jump(JUMP_DEFAULT);
+    assert_eq!(rng.index(), 5 + 2 * JUMP_DEFAULT);
+    assert_eq!(rng.next_u64(), u64(key(3), 5 + 2 * JUMP_DEFAULT));
+  }
+
+  #[test]
+  fn independent_streams_differ() {
+    let streams = Squares::independent_streams::<8>(40);
+    let mut firsts = streams.map(|mut s| s.next_u64());
+    assert_eq!(firsts[0], u64(key(40), 0));
+    assert_eq!(firsts[7], u64(key(47), 0));
+
+    firsts.sort_unstable();
+    assert!(firsts.windows(2).all(|w| w[0] != w[1]));
+    assert!(Squares::independent_streams::<0>(0).is_empty());
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn from_time_keys() {
+    use crate::Key;
+
+    let first = Squares::from_time();
+    assert!(Key::checked(first.key.inner()).is_ok());
+    assert_eq!(first.index(), 0);
+
+    // back to back, within the clock's resolution, only the call counter tells calls apart
+    let n = if cfg!(miri) { 50 } else { 10_000 };
+    let keys: std::collections::HashSet<u64> = (0..n).map(|_| Squares::from_time().key.inner()).collect();
+    assert_eq!(keys.len(), n);
+  }
+
+  #[test]
+  fn skip_bytes_aligns_with_fill() {
+    let k = key(16);
+    let mut whole = [0u8; 40];
+    Squares::with_key(k).fill_bytes(&mut whole);
+
+    for skip in [0, 3, 8, 11] {
+      for len in [1, 5, 13, 20] {
+        let mut rng = Squares::with_key(k);
+        rng.skip_bytes(skip as u64);
+        let mut part = [0u8; 20];
+        rng.fill_bytes(&mut part[..len]);
+        assert_eq!(part[..len], whole[skip..skip + len], "skip {} len {}", skip, len);
+      }
+    }
+
+    // fills continue mid-word, word draws start a fresh one
+    let mut rng = Squares::with_key(k);
+    let mut a = [0u8; 3];
+    let mut b = [0u8; 7];
+    rng.fill_bytes(&mut a);
+    rng.fill_bytes(&mut b);
+    assert_eq!([a.as_slice(), &b].concat(), whole[..10]);
+    assert_eq!(rng.next_u64(), u64(k, 2));
+
+    let mut rng = Squares::with_key(k).with_index(u64::MAX);
+    rng.skip_bytes(u64::MAX);
+    assert_eq!((rng.index, rng.offset), ((u64::MAX / 8).wrapping_add(u64::MAX), 7));
+  }
+
+  #[test]
+  fn reset_to_matches_fresh() {
+    let mut rng = Squares::with_key(key(1)).with_index(99);
+    rng.next_u64();
+    rng.reset_to(key(2));
+
+    let mut fresh = Squares::with_key(key(2));
+    assert_eq!(rng.index(), 0);
+    for _ in 0..10 {
+      assert_eq!(rng.next_u64(), fresh.next_u64());
+    }
+  }
+
+  #[test]
+  fn from_name_streams() {
+    let mut a = Squares::from_name("Overworld");
+    let mut b = Squares::from_name("Overworld");
+    for _ in 0..100 {
+      assert_eq!(a.next_u64(), b.next_u64());
+    }
+
+    let mut firsts: std::vec::Vec<u64> = (0..1_000)
+      .map(|i| Squares::from_name(&std::format!("world-{}", i)).next_u64())
+      .collect();
+    firsts.sort_unstable();
+    firsts.dedup();
+    assert_eq!(firsts.len(), 1_000);
+  }
+
+  /// `fill_bytes` must match the byte layout frozen in `tests/vectors/kat.txt`
+  #[cfg(feature = "rand")]
+  #[test]
+  fn fill_bytes_vectors() {
+    use crate::Key;
+
+    let parse = |s: &str| u64::from_str_radix(s, 16).unwrap();
+    let vectors = include_str!("../tests/vectors/kat.txt");
+    let mut checked = 0;
+
+    for line in vectors.lines().filter(|l| l.starts_with("fill_bytes ")) {
+      let fields: std::vec::Vec<&str> = line.split_whitespace().collect();
+      let len: usize = fields[3].parse().unwrap();
+
+      let mut rng = Squares::with_key(Key::unchecked(parse(fields[1]))).with_index(parse(fields[2]));
+      let mut bytes = [0u8; 64];
+      rng.fill_bytes(&mut bytes[..len]);
+
+      for (i, b) in bytes[..len].iter().enumerate() {
+        assert_eq!(*b as u64, parse(&fields[4][i * 2..i * 2 + 2]), "{}", line);
+      }
+      checked += 1;
+    }
+
+    assert!(checked > 0);
+  }
+
+  #[cfg(all(feature = "rand", feature = "rand_09"))]
+  #[test]
+  fn rand_core_versions_agree() {
+    let k = key(2);
+    let mut a = Squares::with_key(k);
+    let mut b = Squares::with_key(k);
+
+    let mut x = [0u8; 37];
+    let mut y = [0u8; 37];
+    rand_core::RngCore::fill_bytes(&mut a, &mut x);
+    rand_core_09::RngCore::fill_bytes(&mut b, &mut y);
+    assert_eq!(x, y);
+    assert_eq!(a.index(), b.index());
+  }
+}
@@ -0,0 +1,372 @@
+use crate::{derive, u32, u64, Key};
+
+/// Produces a uniformly distributed `u64` in `0..n`.
+///
+/// Uses a widening multiply with rejection (Lemire's method).
+/// Rejected draws (rare unless `n` is huge) are retried at counters derived from `index`,
+/// so every `index` yields exactly one output.
+///
+/// ## Panics
+/// If `n == 0`.
+#[must_use] #[inline(always)]
+pub const fn bounded(key: Key, index: u64, n: u64) -> u64 {
+  assert!(n != 0, "bounded: n must be non-zero");
+
+  let mut attempt = 0;
+  loop {
+    let m = u64(key, derive(index, attempt)) as u128 * n as u128;
+    let lo = m as u64;
+    if lo >= n || lo >= n.wrapping_neg() % n {
+      return (m >> 64) as u64;
+    }
+    attempt += 1;
+  }
+}
+
+/// Fills `out` with unbiased values in `0..n`: position `i` is [`bounded`]`(key, start_index + i, n)`.
+///
+/// Rejections are retried at counters derived from each index rather than at the next index, so
+/// exactly `out.len()` indices are consumed however many draws were rejected; continue at
+/// `start_index + out.len()`.
+///
+/// ## Panics
+/// If `n == 0`.
+#[inline]
+pub fn fill_bounded(key: Key, start_index: u64, n: u64, out: &mut [u64]) {
+  assert!(n != 0, "fill_bounded: n must be non-zero");
+  for (i, x) in out.iter_mut().enumerate() {
+    *x = bounded(key, start_index.wrapping_add(i as u64), n);
+  }
+}
+
+/// Produces two different uniformly distributed values in `0..n`, e.g. crossover points.
+///
+/// The first is [`bounded`] at `start_index`; the second is [`bounded`] at `start_index + 1`,
+/// redrawn at `derive(start_index + 1, t)` for `t = 1, 2, ...` (`u64(DERIVE_KEY, start_index + 1) + t`)
+/// until it differs, about `n / (n - 1)` draws. So two counters are consumed.
+///
+/// ## Panics
+/// If `n < 2`.
+#[must_use]
+pub const fn two_distinct(key: Key, start_index: u64, n: u64) -> (u64, u64) {
+  assert!(n >= 2, "two_distinct: n must be at least 2");
+  let a = bounded(key, start_index, n);
+  let second = start_index.wrapping_add(1);
+
+  let mut attempt = 0;
+  loop {
+    let b = bounded(key, derive(second, attempt), n);
+    if b != a { return (a, b); }
+    attempt += 1;
+  }
+}
+
+/// Quantizes a uniform draw into `levels` equal buckets: returns the bucket in `0..levels`
+/// that `u64(key, index)` falls in, via a single widening multiply.
+///
+/// Unlike [`bounded`] there is no rejection, so each level's probability is off from `1 / levels`
+/// by less than `2^-64`; in exchange every call costs exactly one draw and outputs are monotone in the draw.
+///
+/// ## Panics
+/// If `levels == 0`.
+#[must_use] #[inline(always)]
+pub const fn quantized(key: Key, index: u64, levels: u64) -> u64 {
+  assert!(levels != 0, "quantized: levels must be non-zero");
+  ((u64(key, index) as u128 * levels as u128) >> 64) as u64
+}
+
+/// Scales `u64(key, index)` into the inclusive range `0..=max` by a single widening multiply
+/// by `max + 1`; `max == u64::MAX` returns the draw itself.
+///
+/// Note the bound: [`bounded`]`(key, index, n)` excludes `n`, while `scaled_to` includes `max`, and
+/// so covers the full `u64` range, which `bounded` cannot. Like [`quantized`] there is no rejection,
+/// so each value is off from uniform by less than `2^-64`, and every call costs exactly one draw.
+#[must_use] #[inline(always)]
+pub const fn scaled_to(key: Key, index: u64, max: u64) -> u64 {
+  let draw = u64(key, index);
+  if max == u64::MAX { return draw; }
+  ((draw as u128 * (max as u128 + 1)) >> 64) as u64
+}
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+/// Primitive integers [`uniform`] can draw (sealed).
+///
+/// A draw is made at one of three widths: 32 bits for types up to 32 bits wide (from [`u32()`]),
+/// 64 for `u64`, `i64`, `usize` and `isize` (from [`u64()`], whatever the platform), and 128 for
+/// `u128` and `i128` (from two [`u64()`]s). Types of the same width class give identical results for
+/// the same range, e.g. `uniform::<u8>(k, i, 3, 9)` equals `uniform::<i32>(k, i, 3, 9) as u8`.
+pub trait UniformInt: sealed::Sealed + Copy {
+  /// A uniformly distributed value in `lo..=hi` (see [`uniform`]).
+  fn uniform(key: Key, index: u64, lo: Self, hi: Self) -> Self;
+}
+
+/// A uniform `u32` in `0..=span`, by widening multiply with rejection at 32 bits
+const fn span_u32(key: Key, index: u64, span: u32) -> u32 {
+  if span == u32::MAX { return u32(key, index); }
+  let n = span + 1;
+
+  let mut attempt = 0;
+  loop {
+    let m = u32(key, derive(index, attempt)) as u64 * n as u64;
+    let lo = m as u32;
+    if lo >= n || lo >= n.wrapping_neg() % n {
+      return (m >> 32) as u32;
+    }
+    attempt += 1;
+  }
+}
+
+/// A uniform `u64` in `0..=span`: [`bounded`] over `span + 1` values
+const fn span_u64(key: Key, index: u64, span: u64) -> u64 {
+  if span == u64::MAX { return u64(key, index); }
+  bounded(key, index, span + 1)
+}
+
+/// The upper half of the 256-bit product `a * b`
+const fn mul_hi_u128(a: u128, b: u128) -> u128 {
+  let (a1, a0) = (a >> 64, a as u64 as u128);
+  let (b1, b0) = (b >> 64, b as u64 as u128);
+  let (p01, p10) = (a0 * b1, a1 * b0);
+  let mid = ((a0 * b0) >> 64) + (p01 as u64 as u128) + (p10 as u64 as u128);
+  a1 * b1 + (p01 >> 64) + (p10 >> 64) + (mid >> 64)
+}
+
+/// The `t`-th 128-bit draw for `index`: `u64`s at `derive(index, 2t)` (high half) and `derive(index, 2t + 1)`
+const fn draw_u128(key: Key, index: u64, t: u64) -> u128 {
+  (u64(key, derive(index, 2 * t)) as u128) << 64 | u64(key, derive(index, 2 * t + 1)) as u128
+}
+
+/// A uniform `u128` in `0..=span`, by widening multiply with rejection at 128 bits
+const fn span_u128(key: Key, index: u64, span: u128) -> u128 {
+  if span == u128::MAX { return draw_u128(key, index, 0); }
+  let n = span + 1;
+
+  let mut attempt = 0;
+  loop {
+    let x = draw_u128(key, index, attempt);
+    let lo = x.wrapping_mul(n);
+    if lo >= n || lo >= n.wrapping_neg() % n {
+      return mul_hi_u128(x, n);
+    }
+    attempt += 1;
+  }
+}
+
+macro_rules! uniform_int {
+  ($span:ident: $($t:ty => $u:ty),*) => {$(
+    impl sealed::Sealed for $t {}
+    impl UniformInt for $t {
+      #[inline]
+      fn uniform(key: Key, index: u64, lo: Self, hi: Self) -> Self {
+        assert!(lo <= hi, "uniform: lo must not exceed hi");
+        // the span as a same-width unsigned, so signed ranges wrap correctly
+        let span = (hi as $u).wrapping_sub(lo as $u);
+        lo.wrapping_add($span(key, index, span as _) as $u as $t)
+      }
+    }
+  )*};
+}
+
+uniform_int!(span_u32: u8 => u8, u16 => u16, u32 => u32, i8 => u8, i16 => u16, i32 => u32);
+uniform_int!(span_u64: u64 => u64, usize => usize, i64 => u64, isize => usize);
+uniform_int!(span_u128: u128 => u128, i128 => u128);
+
+/// Produces a uniformly distributed integer of any primitive type in the inclusive range `lo..=hi`,
+/// without bias: `let die: u8 = uniform(key, index, 1, 6);`
+///
+/// Rejected draws are retried at counters derived from `index`, as in [`bounded`];
+/// see [`UniformInt`] for which draws each type uses.
+///
+/// ## Panics
+/// If `lo > hi`.
+#[must_use] #[inline(always)]
+pub fn uniform<T: UniformInt>(key: Key, index: u64, lo: T, hi: T) -> T { T::uniform(key, index, lo, hi) }
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use crate::u64;
+  use super::{bounded, fill_bounded, quantized, scaled_to, two_distinct, uniform};
+
+  #[test]
+  fn fill_bounded_matches_bounded() {
+    let k = key(35);
+    for n in [1, 6, (1 << 63) + 1] {
+      let mut out = [0; 300];
+      fill_bounded(k, u64::MAX - 100, n, &mut out);
+      assert!(out.iter().all(|&x| x < n));
+      let mut again = [0; 300];
+      fill_bounded(k, u64::MAX - 100, n, &mut again);
+      assert_eq!(out, again);
+
+      // consumed exactly `out.len()` indices: halves continue seamlessly
+      let (mut a, mut b) = ([0; 100], [0; 200]);
+      fill_bounded(k, u64::MAX - 100, n, &mut a);
+      fill_bounded(k, (u64::MAX - 100).wrapping_add(100), n, &mut b);
+      assert_eq!((&out[..100], &out[100..]), (&a[..], &b[..]));
+    }
+  }
+
+  #[test]
+  fn scaled_to_inclusive() {
+    let k = key(29);
+    for max in [0, 1, 2, 5] {
+      let mut seen = [false; 6];
+      for i in 0..1_000 {
+        let v = scaled_to(k, i, max);
+        assert!(v <= max);
+        seen[v as usize] = true;
+      }
+      assert!(seen[..=max as usize].iter().all(|&s| s), "{} {:?}", max, seen);
+    }
+
+    assert_eq!(scaled_to(k, 7, u64::MAX), u64(k, 7));
+    assert_eq!(scaled_to(k, 7, 9), quantized(k, 7, 10));
+  }
+
+  #[test]
+  fn bounded_in_range() {
+    let k = key(5);
+    for n in [1, 2, 3, 7, 100, 1 << 40, u64::MAX / 3 * 2, u64::MAX] {
+      for i in 0..1_000 {
+        assert!(bounded(k, i, n) < n);
+      }
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn bounded_uniform() {
+    let k = key(6);
+    let mut counts = [0u32; 6];
+    for i in 0..60_000 {
+      counts[bounded(k, i, 6) as usize] += 1;
+    }
+    for c in counts {
+      assert!((9_500..10_500).contains(&c), "{:?}", counts);
+    }
+  }
+
+  #[test]
+  fn two_distinct_differ() {
+    let k = key(15);
+    let mut pairs = [[0u32; 3]; 3];
+    for i in 0..3_000 {
+      let (a, b) = two_distinct(k, 2 * i, 3);
+      assert!(a != b && a < 3 && b < 3);
+      pairs[a as usize][b as usize] += 1;
+    }
+    // all 6 ordered pairs, each ~500
+    for (a, row) in pairs.iter().enumerate() {
+      for (b, &c) in row.iter().enumerate() {
+        assert!(a == b || (400..600).contains(&c), "{:?}", pairs);
+      }
+    }
+
+    for n in [2, 1 << 40, u64::MAX] {
+      let (a, b) = two_distinct(k, 0, n);
+      assert!(a != b && a < n && b < n);
+      assert_eq!(a, bounded(k, 0, n));
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn quantized_spans_levels() {
+    let k = key(12);
+    let mut counts = [0u32; 10];
+    for i in 0..100_000 {
+      counts[quantized(k, i, 10) as usize] += 1;
+    }
+    for c in counts {
+      // 5 standard deviations around 10k
+      assert!((c as i64 - 10_000).abs() < 475, "{:?}", counts);
+    }
+
+    assert_eq!(quantized(k, 5, 1), 0);
+    assert!((0..1_000).all(|i| quantized(k, i, u64::MAX) < u64::MAX));
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn uniform_small_ranges_per_type() {
+    use core::any::type_name;
+
+    /// counts of each of the 5 values `lo..=lo + 4`, checked against 2_000 each
+    fn check<T: super::UniformInt + PartialEq>(lo: T, values: [T; 5]) {
+      let k = key(13);
+      let mut counts = [0u32; 5];
+      for i in 0..10_000 {
+        let x = uniform(k, i, lo, values[4]);
+        counts[values.iter().position(|&v| v == x).expect("out of range")] += 1;
+      }
+      for c in counts {
+        assert!((c as i32 - 2_000).abs() < 200, "{}: {:?}", type_name::<T>(), counts);
+      }
+    }
+
+    check(0u8, [0, 1, 2, 3, 4]);
+    check(250u8, [250, 251, 252, 253, 254]);
+    check(7u16, [7, 8, 9, 10, 11]);
+    check(1u32, [1, 2, 3, 4, 5]);
+    check(1u64 << 62, core::array::from_fn(|i| (1 << 62) + i as u64));
+    check(0usize, [0, 1, 2, 3, 4]);
+    check(0u128, [0, 1, 2, 3, 4]);
+    // straddling zero
+    check(-2i8, [-2, -1, 0, 1, 2]);
+    check(-2i16, [-2, -1, 0, 1, 2]);
+    check(-2i32, [-2, -1, 0, 1, 2]);
+    check(-2i64, [-2, -1, 0, 1, 2]);
+    check(-2isize, [-2, -1, 0, 1, 2]);
+    check(-2i128, [-2, -1, 0, 1, 2]);
+  }
+
+  #[test]
+  fn uniform_full_and_single_value_ranges() {
+    let k = key(13);
+    for i in 0..200 {
+      assert_eq!(uniform(k, i, u64::MIN, u64::MAX), crate::u64(k, i));
+      // signed ranges are offset from `lo`, here flipping the sign bit
+      assert_eq!(uniform(k, i, i64::MIN, i64::MAX), (crate::u64(k, i) ^ 1 << 63) as i64);
+      assert_eq!(uniform(k, i, u32::MIN, u32::MAX), crate::u32(k, i));
+      let wide = uniform(k, i, u128::MIN, u128::MAX);
+      assert_eq!((wide >> 64) as u64, crate::u64(k, i));
+      assert_eq!(uniform(k, i, i128::MIN, i128::MAX) as u128, wide ^ 1 << 127);
+      let _: i8 = uniform(k, i, i8::MIN, i8::MAX);
+
+      assert_eq!(uniform(k, i, 9u8, 9), 9);
+      assert_eq!(uniform(k, i, -9i16, -9), -9);
+      assert_eq!(uniform(k, i, u64::MAX, u64::MAX), u64::MAX);
+      assert_eq!(uniform(k, i, i128::MIN, i128::MIN), i128::MIN);
+    }
+
+    // both halves of a full-range byte come up
+    let bytes: std::collections::BTreeSet<u8> = (0..5_000).map(|i| uniform(k, i, 0, 255)).collect();
+    assert_eq!(bytes.len(), 256);
+
+    let caught = std::panic::catch_unwind(|| uniform(k, 0, 5u8, 4));
+    assert!(caught.is_err());
+  }
+
+  #[test]
+  fn uniform_consistent_across_types() {
+    let k = key(14);
+    for i in 0..1_000 {
+      let narrow = uniform(k, i, 3u8, 200);
+      assert_eq!(uniform(k, i, 3u16, 200), narrow as u16);
+      assert_eq!(uniform(k, i, 3u32, 200), narrow as u32);
+      assert_eq!(uniform(k, i, 3i32, 200), narrow as i32);
+      assert_eq!(uniform(k, i, -100i8, 97), narrow.wrapping_sub(103) as i8);
+
+      let wide = uniform(k, i, 10u64, 1 << 50);
+      assert_eq!(wide, 10 + bounded(k, i, (1 << 50) - 9));
+      assert_eq!(uniform(k, i, 10usize, 1 << 50) as u64, wide);
+      assert_eq!(uniform(k, i, 10i64, 1 << 50) as u64, wide);
+      assert_eq!(uniform(k, i, -5i64, 5) + 5, uniform(k, i, 0u64, 10) as i64);
+      assert_eq!(uniform(k, i, -5i128, 5) + 5, uniform(k, i, 0u128, 10) as i128);
+    }
+  }
+}
@@ -0,0 +1,156 @@
+use crate::{Inadmissible, Key};
+
+/// first bytes of every record
+const MAGIC: [u8; 3] = *b"SQK";
+/// the record layout written by [`Key::encode_record`]
+const VERSION: u8 = 1;
+
+/// Why [`Key::decode_record`] refused a record
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordError {
+  /// The first three bytes are not `b"SQK"`: not a key record at all.
+  BadMagic,
+  /// A format version this release cannot read, e.g. from a newer writer.
+  UnsupportedVersion(u8),
+  /// The CRC-32 does not match: the record was corrupted.
+  ChecksumMismatch,
+  /// The record is intact, but the key it holds breaks a key rule.
+  Inadmissible(Inadmissible),
+}
+
+impl RecordError {
+  pub const fn message(&self) -> &'static str {
+    match self {
+      RecordError::BadMagic => "Not a key record",
+      RecordError::UnsupportedVersion(_) => "Unsupported key record version",
+      RecordError::ChecksumMismatch => "Key record checksum mismatch",
+      RecordError::Inadmissible(e) => e.message(),
+    }
+  }
+}
+
+/// CRC-32 (IEEE 802.3, reflected, as zlib computes it) of `bytes`
+const fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = !0u32;
+  let mut i = 0;
+  while i < bytes.len() {
+    crc ^= bytes[i] as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+      bit += 1;
+    }
+    i += 1;
+  }
+  !crc
+}
+
+impl Key {
+  /// Writes a 16-byte record of this key for storage, safe to exchange between machines of any endianness:
+  ///
+  /// | bytes    | contents                                   |
+  /// |----------|--------------------------------------------|
+  /// | `0..3`   | magic `b"SQK"`                             |
+  /// | `3`      | format version, `1`                        |
+  /// | `4..12`  | the key, little-endian                     |
+  /// | `12..16` | CRC-32 (zlib's) of bytes `0..12`, little-endian |
+  ///
+  /// This layout is frozen: version 1 records will always decode to the same key.
+  pub const fn encode_record(self, out: &mut [u8; 16]) {
+    out[0] = MAGIC[0];
+    out[1] = MAGIC[1];
+    out[2] = MAGIC[2];
+    out[3] = VERSION;
+    let key = self.inner().to_le_bytes();
+    let mut i = 0;
+    while i < 8 {
+      out[4 + i] = key[i];
+      i += 1;
+    }
+    let (payload, _) = out.split_at(12);
+    let crc = crc32(payload).to_le_bytes();
+    let mut i = 0;
+    while i < 4 {
+      out[12 + i] = crc[i];
+      i += 1;
+    }
+  }
+
+  /// Reads a record written by [`Key::encode_record`], checking in order the magic, the version,
+  /// the checksum, and that the key is admissible.
+  pub const fn decode_record(record: &[u8; 16]) -> Result<Key, RecordError> {
+    if record[0] != MAGIC[0] || record[1] != MAGIC[1] || record[2] != MAGIC[2] {
+      return Err(RecordError::BadMagic);
+    }
+    if record[3] != VERSION { return Err(RecordError::UnsupportedVersion(record[3])); }
+
+    let (payload, _) = record.split_at(12);
+    let stored = u32::from_le_bytes([record[12], record[13], record[14], record[15]]);
+    if crc32(payload) != stored { return Err(RecordError::ChecksumMismatch); }
+
+    let key = u64::from_le_bytes([record[4], record[5], record[6], record[7], record[8], record[9], record[10], record[11]]);
+    match Key::checked(key) {
+      Ok(k) => Ok(k),
+      Err(e) => Err(RecordError::Inadmissible(e)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, Key};
+  use super::{crc32, RecordError};
+
+  fn record(k: Key) -> [u8; 16] {
+    let mut out = [0; 16];
+    k.encode_record(&mut out);
+    out
+  }
+
+  #[test]
+  fn crc32_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b""), 0);
+  }
+
+  #[test]
+  fn round_trip_and_fixture() {
+    for i in [0, 1, u64::MAX] {
+      assert_eq!(Key::decode_record(&record(key(i))).map(Key::inner), Ok(key(i).inner()));
+    }
+
+    // frozen: firmware and backends must agree on these bytes forever (CRC as Python's `zlib.crc32`)
+    let k = Key::unchecked(0xaf9ed4c87b8e4fa5);
+    let fixture = [b'S', b'Q', b'K', 1, 0xa5, 0x4f, 0x8e, 0x7b, 0xc8, 0xd4, 0x9e, 0xaf, 0x85, 0xae, 0xa5, 0x92];
+    assert_eq!(record(k), fixture);
+    assert_eq!(Key::decode_record(&fixture).map(Key::inner), Ok(k.inner()));
+
+    const DECODED: Result<Key, RecordError> = Key::decode_record(&[b'S', b'Q', b'K', 1, 0xa5, 0x4f, 0x8e, 0x7b, 0xc8, 0xd4, 0x9e, 0xaf, 0x85, 0xae, 0xa5, 0x92]);
+    assert!(DECODED.is_ok());
+  }
+
+  #[test]
+  fn rejects_each_corruption() {
+    let good = record(key(5));
+
+    let mut bad = good;
+    bad[0] = b'X';
+    assert!(matches!(Key::decode_record(&bad), Err(RecordError::BadMagic)));
+
+    let mut future = good;
+    future[3] = 2;
+    assert!(matches!(Key::decode_record(&future), Err(RecordError::UnsupportedVersion(2))));
+
+    // every single-bit flip past the header is caught
+    for bit in 32..128 {
+      let mut flipped = good;
+      flipped[bit / 8] ^= 1 << (bit % 8);
+      assert!(matches!(Key::decode_record(&flipped), Err(RecordError::ChecksumMismatch)), "{}", bit);
+    }
+
+    // a well-formed record of a key that breaks the rules
+    let inadmissible = record(Key::unchecked(0x1234_5678_9abc_def0));
+    assert!(matches!(Key::decode_record(&inadmissible), Err(RecordError::Inadmissible(_))));
+    assert_eq!(RecordError::ChecksumMismatch.message(), "Key record checksum mismatch");
+  }
+}
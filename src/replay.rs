@@ -0,0 +1,277 @@
+//! Recording and replaying the values an RNG hands out, to reproduce a failure found
+//! deep inside code that takes `impl RngCore`.
+
+#[cfg(feature = "alloc")] use alloc::vec::Vec;
+
+/// One consumed value: the `index`-th value drawn through a [`ReplayRecorder`].
+///
+/// `next_u32` is recorded zero-extended, and `fill_bytes` as one record per 8 bytes (little-endian, zero-padded).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Record {
+  /// position in the recorded sequence, from 0
+  pub index: u64,
+  pub value: u64,
+}
+
+/// Somewhere a [`ReplayRecorder`] can append its [`Record`]s.
+pub trait ReplayLog {
+  /// Appends one record
+  fn push(&mut self, record: Record);
+}
+
+#[cfg(feature = "alloc")]
+impl ReplayLog for Vec<Record> {
+  fn push(&mut self, record: Record) { Vec::push(self, record) }
+}
+
+/// A fixed-size log keeping the most recent `N` records, for `no_std` use.
+#[derive(Clone, Debug)]
+pub struct RingLog<const N: usize> {
+  records: [Record; N],
+  pushed: u64,
+}
+
+impl<const N: usize> RingLog<N> {
+  /// An empty log
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { records: [Record { index: 0, value: 0 }; N], pushed: 0 }
+  }
+
+  /// Total records ever pushed, including those overwritten
+  #[must_use]
+  pub const fn pushed(&self) -> u64 { self.pushed }
+
+  /// The retained records, oldest first.
+  pub fn iter(&self) -> impl Iterator<Item = Record> + '_ {
+    let kept = (self.pushed as usize).min(N);
+    let start = if N == 0 { 0 } else { (self.pushed % N as u64) as usize };
+    let (newer, older) = self.records.split_at(if kept < N { 0 } else { start });
+    older.iter().chain(newer).take(kept).copied()
+  }
+}
+
+impl<const N: usize> Default for RingLog<N> {
+  fn default() -> Self { Self::new() }
+}
+
+impl<const N: usize> ReplayLog for RingLog<N> {
+  fn push(&mut self, record: Record) {
+    if N == 0 { return; }
+    self.records[(self.pushed % N as u64) as usize] = record;
+    self.pushed = self.pushed.wrapping_add(1);
+  }
+}
+
+/// Wraps an RNG, delegating to it while logging every value it hands out to `log`.
+#[derive(Debug)]
+pub struct ReplayRecorder<'a, R, L: ReplayLog> {
+  rng: R,
+  log: &'a mut L,
+  index: u64,
+}
+
+impl<'a, R, L: ReplayLog> ReplayRecorder<'a, R, L> {
+  /// Records what `rng` hands out into `log`
+  #[must_use]
+  pub fn new(rng: R, log: &'a mut L) -> Self {
+    Self { rng, log, index: 0 }
+  }
+
+  /// Unwraps the inner RNG
+  pub fn into_inner(self) -> R { self.rng }
+
+  fn record(&mut self, value: u64) {
+    self.log.push(Record { index: self.index, value });
+    self.index = self.index.wrapping_add(1);
+  }
+
+  fn record_bytes(&mut self, bytes: &[u8]) {
+    for chunk in bytes.chunks(8) {
+      let mut word = [0; 8];
+      word[..chunk.len()].copy_from_slice(chunk);
+      self.record(u64::from_le_bytes(word));
+    }
+  }
+}
+
+/// The `rand_core` 0.6 error code `Replay::try_fill_bytes` returns when the records run out
+#[cfg(feature = "rand")]
+pub const REPLAY_EXHAUSTED: u32 = rand_core::Error::CUSTOM_START + 1;
+
+/// Plays back recorded values as an RNG.
+///
+/// The code under replay must make the same sequence of calls as when recording.
+/// Running out of records panics (or, for `try_fill_bytes`, errors with [`REPLAY_EXHAUSTED`]).
+#[derive(Clone, Debug)]
+pub struct Replay<I> {
+  records: I,
+}
+
+impl<I: Iterator<Item = Record>> Replay<I> {
+  /// Plays back `records` in order, e.g. `vec.iter().copied()` or `ring.iter()`
+  #[must_use]
+  pub fn new(records: impl IntoIterator<IntoIter = I>) -> Self {
+    Self { records: records.into_iter() }
+  }
+
+  fn next_value(&mut self) -> Option<u64> {
+    self.records.next().map(|r| r.value)
+  }
+
+  fn next(&mut self) -> u64 {
+    self.next_value().expect("Replay: recorded values exhausted")
+  }
+
+  /// Fills `dest` from records, returning `false` if they ran out.
+  fn fill(&mut self, dest: &mut [u8]) -> bool {
+    for chunk in dest.chunks_mut(8) {
+      let Some(value) = self.next_value() else { return false; };
+      let n = chunk.len();
+      chunk.copy_from_slice(&value.to_le_bytes()[..n]);
+    }
+    true
+  }
+}
+
+#[cfg(feature = "rand")]
+impl<R: rand_core::RngCore, L: ReplayLog> rand_core::RngCore for ReplayRecorder<'_, R, L> {
+  fn next_u32(&mut self) -> u32 {
+    let v = self.rng.next_u32();
+    self.record(v as u64);
+    v
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let v = self.rng.next_u64();
+    self.record(v);
+    v
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.rng.fill_bytes(dest);
+    self.record_bytes(dest);
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+    self.rng.try_fill_bytes(dest)?;
+    self.record_bytes(dest);
+    Ok(())
+  }
+}
+
+#[cfg(feature = "rand_09")]
+impl<R: rand_core_09::RngCore, L: ReplayLog> rand_core_09::RngCore for ReplayRecorder<'_, R, L> {
+  fn next_u32(&mut self) -> u32 {
+    let v = self.rng.next_u32();
+    self.record(v as u64);
+    v
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let v = self.rng.next_u64();
+    self.record(v);
+    v
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.rng.fill_bytes(dest);
+    self.record_bytes(dest);
+  }
+}
+
+#[cfg(feature = "rand")]
+impl<I: Iterator<Item = Record>> rand_core::RngCore for Replay<I> {
+  fn next_u32(&mut self) -> u32 { self.next() as u32 }
+
+  fn next_u64(&mut self) -> u64 { self.next() }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    assert!(self.fill(dest), "Replay: recorded values exhausted");
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+    if self.fill(dest) { return Ok(()); }
+    // `Error::new` needs rand_core's `std`; a code works everywhere
+    Err(core::num::NonZeroU32::new(REPLAY_EXHAUSTED).unwrap().into())
+  }
+}
+
+#[cfg(feature = "rand_09")]
+impl<I: Iterator<Item = Record>> rand_core_09::RngCore for Replay<I> {
+  fn next_u32(&mut self) -> u32 { self.next() as u32 }
+
+  fn next_u64(&mut self) -> u64 { self.next() }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    assert!(self.fill(dest), "Replay: recorded values exhausted");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(feature = "rand")] use crate::{key, rand::Squares};
+  #[cfg(feature = "rand")] use super::ReplayRecorder;
+  use super::{Record, ReplayLog, RingLog};
+
+  #[test]
+  fn ring_log_wraps() {
+    let mut ring = RingLog::<4>::new();
+    assert_eq!(ring.iter().count(), 0);
+    for i in 0..3 {
+      ring.push(Record { index: i, value: i * 10 });
+    }
+    assert_eq!(ring.iter().map(|r| r.index).collect::<std::vec::Vec<_>>(), [0, 1, 2]);
+
+    for i in 3..10 {
+      ring.push(Record { index: i, value: i * 10 });
+    }
+    assert_eq!(ring.pushed(), 10);
+    assert_eq!(ring.iter().map(|r| r.index).collect::<std::vec::Vec<_>>(), [6, 7, 8, 9]);
+
+    let mut none = RingLog::<0>::new();
+    none.push(Record::default());
+    assert_eq!(none.iter().count(), 0);
+  }
+
+  /// downstream code mixing every kind of draw
+  #[cfg(feature = "rand")]
+  fn simulate(rng: &mut impl rand_core::RngCore) -> (u64, u32, [u8; 13]) {
+    let a = rng.next_u64();
+    let b = rng.next_u32();
+    let mut c = [0; 13];
+    rng.fill_bytes(&mut c);
+    (a ^ rng.next_u64(), b, c)
+  }
+
+  #[cfg(all(feature = "rand", feature = "alloc"))]
+  #[test]
+  fn record_then_replay() {
+    use super::Replay;
+
+    let mut log = std::vec::Vec::new();
+    let mut recorder = ReplayRecorder::new(Squares::with_key(key(14)), &mut log);
+    let original = simulate(&mut recorder);
+    assert_eq!(log.len(), 5);
+    assert!(log.iter().enumerate().all(|(i, r)| r.index == i as u64));
+
+    let mut replay = Replay::new(log.iter().copied());
+    assert_eq!(simulate(&mut replay), original);
+    let err = rand_core::RngCore::try_fill_bytes(&mut replay, &mut [0; 1]).unwrap_err();
+    assert_eq!(err.code().map(|c| c.get()), Some(super::REPLAY_EXHAUSTED));
+  }
+
+  #[cfg(feature = "rand")]
+  #[test]
+  fn record_into_ring() {
+    let mut ring = RingLog::<3>::new();
+    let mut recorder = ReplayRecorder::new(Squares::with_key(key(14)), &mut ring);
+    simulate(&mut recorder);
+    let tail = rand_core::RngCore::next_u64(&mut recorder);
+
+    assert_eq!(ring.pushed(), 6);
+    let kept: std::vec::Vec<Record> = ring.iter().collect();
+    assert_eq!(kept.iter().map(|r| r.index).collect::<std::vec::Vec<_>>(), [3, 4, 5]);
+    assert_eq!(kept[2].value, tail);
+  }
+}
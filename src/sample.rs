@@ -0,0 +1,59 @@
+use crate::{f32, f64, u32, u64, Key};
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+/// Types [`sample`] can produce (sealed).
+///
+/// Integers are uniform over their full range; `u8` and `u16` are the upper bits of [`u32()`],
+/// `bool` its top bit. Floats are uniform in `[0, 1)`, as [`f32()`] and [`f64()`].
+pub trait SampleType: sealed::Sealed + Sized {
+  /// The value at `index` of the stream under `key`.
+  fn sample(key: Key, index: u64) -> Self;
+}
+
+macro_rules! sample_type {
+  ($($t:ty => |$key:ident, $index:ident| $body:expr;)*) => {$(
+    impl sealed::Sealed for $t {}
+    impl SampleType for $t {
+      #[inline(always)]
+      fn sample($key: Key, $index: u64) -> Self { $body }
+    }
+  )*};
+}
+
+sample_type! {
+  u8 => |key, index| (u32(key, index) >> 24) as u8;
+  u16 => |key, index| (u32(key, index) >> 16) as u16;
+  u32 => |key, index| u32(key, index);
+  u64 => |key, index| u64(key, index);
+  f32 => |key, index| f32(key, index);
+  f64 => |key, index| f64(key, index);
+  bool => |key, index| u32(key, index) >> 31 == 1;
+}
+
+/// One entry point for every [`SampleType`]: `let x: f64 = squares::sample(key, 3);`
+#[must_use] #[inline(always)]
+pub fn sample<T: SampleType>(key: Key, index: u64) -> T { T::sample(key, index) }
+
+#[cfg(test)]
+mod tests {
+  use crate::{f64, key, u32, u64};
+  use super::sample;
+
+  #[test]
+  fn sample_dispatches() {
+    let k = key(8);
+    assert_eq!(sample::<u64>(k, 3), u64(k, 3));
+    assert_eq!(sample::<u32>(k, 3), u32(k, 3));
+    assert_eq!(sample::<u8>(k, 3), u32(k, 3).to_be_bytes()[0]);
+    assert_eq!(sample::<u16>(k, 3), (u32(k, 3) >> 16) as u16);
+    let x: f64 = sample(k, 3);
+    assert_eq!(x, f64(k, 3));
+    assert!((0.0..1.0).contains(&sample::<f32>(k, 3)));
+
+    let heads = (0..1_000).filter(|&i| sample::<bool>(k, i)).count();
+    assert!((400..600).contains(&heads), "{}", heads);
+  }
+}
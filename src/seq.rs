@@ -0,0 +1,663 @@
+#[cfg(feature = "alloc")] use alloc::vec::Vec;
+#[cfg(feature = "alloc")] use crate::derive;
+use crate::{bounded, f64, f64_open, Key};
+
+/// Shuffles `items` uniformly at random in place (Fisher–Yates), consuming indices
+/// `start_index..start_index + items.len() - 1`: step `s` swaps position `len - 1 - s`
+/// with one drawn by [`bounded`] among the positions up to it.
+pub fn shuffle<T>(key: Key, start_index: u64, items: &mut [T]) {
+  for (s, i) in (1..items.len()).rev().enumerate() {
+    let j = bounded(key, start_index.wrapping_add(s as u64), i as u64 + 1) as usize;
+    items.swap(i, j);
+  }
+}
+
+/// A random byte substitution table and its inverse: `sbox[b]` is the image of `b`, and
+/// `inverse[sbox[b]] == b`. For reversible obfuscation, not encryption.
+///
+/// The table is `0..=255` put through [`shuffle`] at `index`, consuming indices `index..index + 255`.
+#[must_use]
+pub fn random_sbox(key: Key, index: u64) -> ([u8; 256], [u8; 256]) {
+  let mut sbox: [u8; 256] = core::array::from_fn(|b| b as u8);
+  shuffle(key, index, &mut sbox);
+  let mut inverse = [0; 256];
+  for (b, &image) in sbox.iter().enumerate() {
+    inverse[image as usize] = b as u8;
+  }
+  (sbox, inverse)
+}
+
+/// Chooses `k` elements of `items` uniformly at random and moves them, in random order, to the
+/// front: the first `k` steps of a (forward) Fisher–Yates shuffle. Returns `(chosen, rest)`;
+/// `rest` holds the unchosen elements in no particular order.
+///
+/// Step `i` swaps position `i` with one drawn by [`bounded`] at `index + i` among positions `i..`,
+/// so exactly `k` counters are consumed. `k > items.len()` is clamped, giving a full shuffle.
+pub fn partial_shuffle<T>(key: Key, index: u64, items: &mut [T], k: usize) -> (&mut [T], &mut [T]) {
+  let len = items.len();
+  let k = k.min(len);
+  for i in 0..k {
+    let j = i + bounded(key, index.wrapping_add(i as u64), (len - i) as u64) as usize;
+    items.swap(i, j);
+  }
+  items.split_at_mut(k)
+}
+
+/// Flips each bit of `genome` independently with probability `rate` (bit `p` is bit `p % 8` of
+/// byte `p / 8`), e.g. for genetic-algorithm mutation.
+///
+/// Rather than a draw per bit, the gaps between flipped bits are drawn from the geometric
+/// distribution, `floor(ln(u) / ln(1 - rate))` with `u` = [`f64_open`], so the work is proportional
+/// to the number of flips. Gap `s` (the one before the `s`-th flip, the last running past the end)
+/// is drawn at `index + s`: `f + 1` counters are consumed for `f` flips. `rate = 0` and `rate = 1`
+/// draw nothing, leaving the genome untouched and flipping every bit respectively.
+///
+/// ## Panics
+/// If `rate` is not in `[0, 1]`.
+pub fn mutate_bits(key: Key, index: u64, genome: &mut [u8], rate: f64) {
+  assert!((0.0..=1.0).contains(&rate), "mutate_bits: rate must be in [0, 1]");
+  if rate == 0.0 { return; }
+  if rate == 1.0 {
+    for byte in genome { *byte = !*byte; }
+    return;
+  }
+
+  let bits = genome.len() as u64 * 8;
+  let log_keep = libm::log1p(-rate);
+  let mut next = 0u64;
+  let mut s = 0u64;
+  loop {
+    // saturating float-to-int conversion: huge gaps just end the walk
+    next = next.saturating_add((libm::log(f64_open(key, index.wrapping_add(s))) / log_keep) as u64);
+    s += 1;
+    if next >= bits { return; }
+    genome[(next / 8) as usize] ^= 1 << (next % 8);
+    next += 1;
+  }
+}
+
+/// Returned by [`derange`] for a single element, which has no derangement.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoDerangement;
+
+/// Permutes `items` uniformly at random among the permutations that move every element
+/// (derangements), e.g. for secret-santa assignments.
+///
+/// Runs the Fisher–Yates shuffle of [`shuffle`] and restarts as soon as an element is fixed in
+/// its original position, which is equivalent to rejecting whole shuffles but usually aborts early.
+/// A shuffle is a derangement with probability about `1/e`, so about `e ≈ 2.72` attempts are
+/// made on average. With `n = items.len()`, attempt `t` consumes counters from
+/// `derive(index, t * n)` onward (`derive(index, 0) = index`, otherwise
+/// `u64(DERIVE_KEY, index) + k`), so attempts never share one.
+///
+/// An empty slice is trivially deranged (`Ok`); a single element can't be, and is left as is.
+#[cfg(feature = "alloc")]
+pub fn derange<T>(key: Key, index: u64, items: &mut [T]) -> Result<(), NoDerangement> {
+  let n = items.len();
+  if n == 1 { return Err(NoDerangement); }
+
+  // original position of the element now at each position; each restart shuffles the
+  // current arrangement, which is as uniform as shuffling the original one
+  let mut origin: Vec<usize> = (0..n).collect();
+  let mut attempt = 0;
+  'attempt: loop {
+    let start = derive(index, attempt * n as u64);
+    attempt += 1;
+    for (s, i) in (1..n).rev().enumerate() {
+      let j = bounded(key, start.wrapping_add(s as u64), i as u64 + 1) as usize;
+      items.swap(i, j);
+      origin.swap(i, j);
+      if origin[i] == i { continue 'attempt; }
+    }
+    if n == 0 || origin[0] != 0 { return Ok(()); }
+  }
+}
+
+/// Chooses `k` distinct elements of `items` uniformly at random, in random order.
+///
+/// Performs a partial Fisher–Yates shuffle over the indices of `items`,
+/// consuming indices `start_index..start_index + k`.
+/// If `k > items.len()`, all items are returned (shuffled).
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn choose_multiple<T: Clone>(items: &[T], key: Key, start_index: u64, k: usize) -> Vec<T> {
+  let len = items.len();
+  let k = k.min(len);
+
+  let mut indices: Vec<usize> = (0..len).collect();
+  for i in 0..k {
+    let j = i + bounded(key, start_index.wrapping_add(i as u64), (len - i) as u64) as usize;
+    indices.swap(i, j);
+  }
+
+  indices[..k].iter().map(|&i| items[i].clone()).collect()
+}
+
+/// Chooses up to `k` distinct positions of `weights` with probability proportional to weight,
+/// as successive weighted draws without replacement would (Efraimidis–Spirakis), writing them to
+/// `out` in the order those draws would pick them. Returns how many were written: `k`, or the number
+/// of positive weights if that is smaller. Zero weights are never chosen.
+///
+/// Item `i` is ranked by `ln(u_i) / weights[i]` with `u_i = 1 - f64(key, index + i)`, the same
+/// ranking [`WeightedReservoir`] applies to its offers, keeping the best `k` in a heap inside `out`:
+/// `O(n log k)` time and no allocation.
+///
+/// ## Panics
+/// If a weight is negative or not finite, or `out` is shorter than `k` (clamped to `weights.len()`).
+pub fn weighted_sample_without_replacement(key: Key, index: u64, weights: &[f64], k: usize, out: &mut [usize]) -> usize {
+  let k = k.min(weights.len());
+  assert!(out.len() >= k, "weighted_sample_without_replacement: out is shorter than k");
+  // priorities are recomputed rather than stored, to keep the heap in the caller's buffer
+  let priority = |i: usize| libm::log(1.0 - f64(key, index.wrapping_add(i as u64))) / weights[i];
+
+  let eligible = weights.iter().enumerate().filter(|&(_, &w)| {
+    assert!(w >= 0.0 && w.is_finite(), "weighted_sample_without_replacement: weights must be finite and non-negative");
+    w != 0.0
+  });
+  top_k(eligible.map(|(i, _)| i), &mut out[..k], &priority)
+}
+
+/// Samples a category from unnormalized log-probabilities: returns the position `i` maximizing
+/// `logits[i] + g_i`, which is distributed as `softmax(logits)` (the Gumbel-max trick), in one pass.
+///
+/// The Gumbel noise is `g_i = -ln(-ln(u_i))` with `u_i = f64_open(key, index + i)`.
+/// A logit of `-inf` is never chosen; `None` if `logits` is empty or all `-inf`.
+///
+/// ## Panics
+/// If a logit is NaN.
+#[must_use]
+pub fn gumbel_max(key: Key, index: u64, logits: &[f64]) -> Option<usize> {
+  let mut best: Option<(usize, f64)> = None;
+  for (i, &logit) in logits.iter().enumerate() {
+    assert!(!logit.is_nan(), "gumbel_max: logits must not be NaN");
+    if logit == f64::NEG_INFINITY { continue; }
+    let score = logit + gumbel(key, index.wrapping_add(i as u64));
+    if best.is_none_or(|(_, b)| score > b) { best = Some((i, score)); }
+  }
+  best.map(|(i, _)| i)
+}
+
+/// The top `k` positions of `logits` by the perturbed scores of [`gumbel_max`], best first, written
+/// to `out`: `k` categories drawn without replacement with softmax probabilities. Returns how many
+/// were written: `k`, or the number of logits above `-inf` if that is smaller.
+///
+/// ## Panics
+/// If a logit is NaN, or `out` is shorter than `k` (clamped to `logits.len()`).
+pub fn gumbel_top_k(key: Key, index: u64, logits: &[f64], k: usize, out: &mut [usize]) -> usize {
+  let k = k.min(logits.len());
+  assert!(out.len() >= k, "gumbel_top_k: out is shorter than k");
+  let score = |i: usize| logits[i] + gumbel(key, index.wrapping_add(i as u64));
+
+  let eligible = logits.iter().enumerate().filter(|&(_, &l)| {
+    assert!(!l.is_nan(), "gumbel_top_k: logits must not be NaN");
+    l != f64::NEG_INFINITY
+  });
+  top_k(eligible.map(|(i, _)| i), &mut out[..k], &score)
+}
+
+/// standard Gumbel noise at `index`
+fn gumbel(key: Key, index: u64) -> f64 {
+  -libm::log(-libm::log(f64_open(key, index)))
+}
+
+/// Keeps the `heap.len()` candidates of highest `priority` in `heap`, best first
+/// (ties going to the earlier candidate), and returns how many were found.
+fn top_k(candidates: impl Iterator<Item = usize>, heap: &mut [usize], priority: &impl Fn(usize) -> f64) -> usize {
+  let k = heap.len();
+  // min-heap on priority: `heap[0]` is the weakest kept
+  let mut filled = 0;
+  for i in candidates {
+    if filled < k {
+      heap[filled] = i;
+      filled += 1;
+      sift_up(heap, filled - 1, priority);
+    } else if filled > 0 && priority(i) > priority(heap[0]) {
+      heap[0] = i;
+      sift_down(&mut heap[..filled], 0, priority);
+    }
+  }
+
+  // heap sort; repeatedly moving the weakest to the back leaves the strongest first
+  for end in (1..filled).rev() {
+    heap.swap(0, end);
+    sift_down(&mut heap[..end], 0, priority);
+  }
+  filled
+}
+
+/// restores the min-heap (by `priority`) above position `at`
+fn sift_up(heap: &mut [usize], mut at: usize, priority: &impl Fn(usize) -> f64) {
+  while at > 0 {
+    let parent = (at - 1) / 2;
+    if priority(heap[at]) >= priority(heap[parent]) { break; }
+    heap.swap(at, parent);
+    at = parent;
+  }
+}
+
+/// restores the min-heap (by `priority`) below position `at`
+fn sift_down(heap: &mut [usize], mut at: usize, priority: &impl Fn(usize) -> f64) {
+  loop {
+    let (l, r) = (2 * at + 1, 2 * at + 2);
+    let mut least = at;
+    if l < heap.len() && priority(heap[l]) < priority(heap[least]) { least = l; }
+    if r < heap.len() && priority(heap[r]) < priority(heap[least]) { least = r; }
+    if least == at { return; }
+    heap.swap(at, least);
+    at = least;
+  }
+}
+
+/// Weighted reservoir sampling (Efraimidis–Spirakis A-Res) over a stream of unknown length.
+///
+/// Keeps up to `capacity` item ids; an item's chance of being retained grows with its weight.
+/// Each offer consumes one index, starting at `start_index`, so the sample is fully determined
+/// by the key, the start index, and the order of offers.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct WeightedReservoir {
+  capacity: usize,
+  key: Key,
+  index: u64,
+  items: Vec<u64>,
+  /// `ln(u) / weight` of each retained item; the largest `capacity` of these are kept
+  priorities: Vec<f64>,
+}
+
+#[cfg(feature = "alloc")]
+impl WeightedReservoir {
+  /// An empty reservoir holding at most `capacity` items, drawing from `start_index` onward.
+  #[must_use]
+  pub fn new(capacity: usize, key: Key, start_index: u64) -> Self {
+    Self {
+      capacity,
+      key,
+      index: start_index,
+      items: Vec::with_capacity(capacity),
+      priorities: Vec::with_capacity(capacity),
+    }
+  }
+
+  /// Offers the next item of the stream. Items with weight `0` are never retained.
+  ///
+  /// ## Panics
+  /// If `weight` is negative, infinite, or NaN.
+  pub fn offer(&mut self, item_id: u64, weight: f64) {
+    assert!(weight >= 0.0 && weight.is_finite(), "WeightedReservoir::offer: weight must be finite and non-negative");
+
+    // u in (0, 1], so ln(u) is finite
+    let u = 1.0 - f64(self.key, self.index);
+    self.index = self.index.wrapping_add(1);
+    if weight == 0.0 || self.capacity == 0 { return; }
+    let priority = libm::log(u) / weight;
+
+    if self.items.len() < self.capacity {
+      self.items.push(item_id);
+      self.priorities.push(priority);
+      return;
+    }
+
+    let mut min = 0;
+    for (i, &p) in self.priorities.iter().enumerate() {
+      if p < self.priorities[min] { min = i; }
+    }
+    if priority > self.priorities[min] {
+      self.items[min] = item_id;
+      self.priorities[min] = priority;
+    }
+  }
+
+  /// The retained item ids, in no particular order.
+  #[must_use]
+  pub fn sample(&self) -> &[u64] { &self.items }
+}
+
+#[cfg(test)]
+mod tests {
+  #[test]
+  fn shuffle_permutes() {
+    use crate::key;
+    use super::shuffle;
+
+    let mut firsts = [0u32; 8];
+    for start in 0..(if cfg!(miri) { 50 } else { 4_000 }) {
+      let mut items: [u8; 8] = core::array::from_fn(|i| i as u8);
+      shuffle(key(4), start * 7, &mut items);
+      let mut sorted = items;
+      sorted.sort_unstable();
+      assert_eq!(sorted, core::array::from_fn(|i| i as u8));
+      firsts[items[0] as usize] += 1;
+    }
+    if !cfg!(miri) {
+      assert!(firsts.iter().all(|&c| (c as i32 - 500).abs() < 110), "{:?}", firsts);
+    }
+
+    shuffle::<u8>(key(4), 0, &mut []);
+    let mut one = [9];
+    shuffle(key(4), 0, &mut one);
+    assert_eq!(one, [9]);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn weighted_sampling_inclusion_probabilities() {
+    use crate::key;
+    use super::weighted_sample_without_replacement as sample;
+
+    // exhaustive: probability of each ordered draw (a, b) without replacement
+    let weights = [1.0, 2.0, 3.0, 4.0];
+    let total: f64 = weights.iter().sum();
+    let mut inclusion = [0.0; 4];
+    for a in 0..4 {
+      for b in (0..4).filter(|&b| b != a) {
+        let p = weights[a] / total * weights[b] / (total - weights[a]);
+        inclusion[a] += p;
+        inclusion[b] += p;
+      }
+    }
+
+    let n = 20_000;
+    let mut counts = [0u32; 4];
+    let mut firsts = [0u32; 4];
+    for s in 0..n {
+      let mut out = [0; 2];
+      assert_eq!(sample(key(8), s * 4, &weights, 2, &mut out), 2);
+      assert_ne!(out[0], out[1]);
+      for i in out { counts[i] += 1; }
+      firsts[out[0]] += 1;
+    }
+    for i in 0..4 {
+      let observed = counts[i] as f64 / n as f64;
+      assert!((observed - inclusion[i]).abs() < 0.015, "{} {:?} {:?}", i, counts, inclusion);
+      // the first pick is a single weighted draw
+      assert!((firsts[i] as f64 / n as f64 - weights[i] / total).abs() < 0.015, "{:?}", firsts);
+    }
+  }
+
+  #[test]
+  fn weighted_sampling_zero_weights_and_small_k() {
+    use crate::key;
+    use super::weighted_sample_without_replacement as sample;
+
+    let weights = [0.0, 5.0, 0.0, 1.0, 0.0];
+    let mut out = [9; 5];
+    assert_eq!(sample(key(8), 0, &weights, 10, &mut out), 2);
+    let mut chosen = [out[0], out[1]];
+    chosen.sort_unstable();
+    assert_eq!(chosen, [1, 3]);
+    assert_eq!(sample(key(8), 0, &weights, 0, &mut []), 0);
+
+    // the same ranking as a reservoir fed the items in order
+    #[cfg(feature = "alloc")]
+    {
+      let weights = [0.5, 1.0, 2.0, 3.0, 0.1, 7.0];
+      let mut out = [0; 3];
+      sample(key(8), 100, &weights, 3, &mut out);
+      let mut reservoir = super::WeightedReservoir::new(3, key(8), 100);
+      for (i, &w) in weights.iter().enumerate() { reservoir.offer(i as u64, w); }
+      let mut kept: std::vec::Vec<usize> = reservoir.sample().iter().map(|&i| i as usize).collect();
+      kept.sort_unstable();
+      out.sort_unstable();
+      assert_eq!(&out[..], &kept[..]);
+    }
+
+    let caught = std::panic::catch_unwind(|| sample(key(8), 0, &[1.0, -1.0], 1, &mut [0]));
+    assert!(caught.is_err());
+  }
+
+  #[test]
+  fn random_sbox_inverts() {
+    use crate::key;
+    use super::{random_sbox, shuffle};
+
+    let (sbox, inverse) = random_sbox(key(36), 9);
+    let mut seen = [false; 256];
+    for b in 0..=255u8 {
+      seen[sbox[b as usize] as usize] = true;
+      assert_eq!(inverse[sbox[b as usize] as usize], b);
+      assert_eq!(sbox[inverse[b as usize] as usize], b);
+    }
+    assert!(seen.iter().all(|&s| s));
+
+    let mut expected: [u8; 256] = core::array::from_fn(|b| b as u8);
+    shuffle(key(36), 9, &mut expected);
+    assert_eq!(sbox, expected);
+    assert_ne!(random_sbox(key(36), 10).0, sbox);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn random_sbox_images_uniform() {
+    use crate::key;
+    use super::random_sbox;
+
+    // where byte 0x5a lands, over tables on disjoint counters: chi-square with 255 degrees of freedom
+    let tables = 25_600;
+    let mut counts = [0u32; 256];
+    for t in 0..tables {
+      counts[random_sbox(key(36), t * 255).0[0x5a] as usize] += 1;
+    }
+    let expected = tables as f64 / 256.0;
+    let chi2: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+    assert!(chi2 < 255.0 + 6.0 * 22.6, "{}", chi2);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn gumbel_max_matches_softmax() {
+    use crate::key;
+    use super::gumbel_max;
+
+    for logits in [[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [-50.0, -51.5, -49.0]] {
+      let max = logits.iter().fold(f64::NEG_INFINITY, |m, &l| m.max(l));
+      let exp = logits.map(|l| libm::exp(l - max));
+      let total: f64 = exp.iter().sum();
+
+      let n = 20_000;
+      let mut counts = [0u32; 3];
+      for s in 0..n {
+        counts[gumbel_max(key(34), s * 3, &logits).unwrap()] += 1;
+      }
+      for i in 0..3 {
+        assert!((counts[i] as f64 / n as f64 - exp[i] / total).abs() < 0.015, "{:?} {:?}", logits, counts);
+      }
+    }
+  }
+
+  #[test]
+  fn gumbel_edge_cases() {
+    use crate::key;
+    use super::{gumbel_max, gumbel_top_k};
+
+    let k = key(34);
+    assert_eq!(gumbel_max(k, 0, &[]), None);
+    assert_eq!(gumbel_max(k, 0, &[f64::NEG_INFINITY; 3]), None);
+    for i in 0..200 {
+      assert_eq!(gumbel_max(k, i, &[f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY]), Some(1));
+      // tied logits are broken by the noise, not the position
+      assert!(gumbel_max(k, i, &[1.0; 4]).is_some());
+    }
+    assert!((0..200).map(|i| gumbel_max(k, i, &[1.0; 4])).any(|c| c != Some(0)));
+
+    let logits = [0.5, f64::NEG_INFINITY, 2.0, -1.0, f64::NEG_INFINITY];
+    let mut out = [9; 5];
+    assert_eq!(gumbel_top_k(k, 7, &logits, 5, &mut out), 3);
+    assert_eq!(out[0], gumbel_max(k, 7, &logits).unwrap());
+    let mut chosen = [out[0], out[1], out[2]];
+    chosen.sort_unstable();
+    assert_eq!(chosen, [0, 2, 3]);
+    assert_eq!(gumbel_top_k(k, 7, &logits, 0, &mut []), 0);
+
+    let caught = std::panic::catch_unwind(|| gumbel_max(k, 0, &[0.0, f64::NAN]));
+    assert!(caught.is_err());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn mutate_bits_rates() {
+    use crate::key;
+    use super::mutate_bits;
+
+    for (rate, genomes) in [(1e-4, 100), (0.01, 20), (0.5, 4)] {
+      let mut flips = 0;
+      // per-position counts catch flips bunching up at the start
+      let mut halves = [0u32; 2];
+      for g in 0..genomes {
+        let mut genome = [0u8; 10_000];
+        mutate_bits(key(9), g << 40, &mut genome, rate);
+        for (i, &b) in genome.iter().enumerate() {
+          flips += b.count_ones();
+          halves[(i >= 5_000) as usize] += b.count_ones();
+        }
+      }
+      let expected = rate * (genomes * 80_000) as f64;
+      let sd = (expected * (1.0 - rate)).sqrt();
+      assert!((flips as f64 - expected).abs() < 5.0 * sd, "rate {}: {} flips, expected {}", rate, flips, expected);
+      assert!((halves[0] as f64 - halves[1] as f64).abs() < 5.0 * sd, "rate {}: {:?}", rate, halves);
+    }
+
+    let mut genome = [0x5a; 64];
+    mutate_bits(key(9), 0, &mut genome, 0.0);
+    assert_eq!(genome, [0x5a; 64]);
+    mutate_bits(key(9), 0, &mut genome, 1.0);
+    assert_eq!(genome, [0xa5; 64]);
+
+    let (mut a, mut b) = ([0u8; 100], [0u8; 100]);
+    mutate_bits(key(9), 3, &mut a, 0.05);
+    mutate_bits(key(9), 3, &mut b, 0.05);
+    assert_eq!(a, b);
+    mutate_bits(key(9), 0, &mut [], 0.5);
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn partial_shuffle_uniform_prefix() {
+    use crate::{bounded, key};
+    use super::partial_shuffle;
+
+    // ordered pairs from 4 elements: 12, each expected 1_000 times
+    let mut counts = [[0u32; 4]; 4];
+    for index in 0..12_000 {
+      let mut items = [0usize, 1, 2, 3];
+      let (chosen, rest) = partial_shuffle(key(7), index * 2, &mut items, 2);
+      assert_eq!((chosen.len(), rest.len()), (2, 2));
+      let mut all = [chosen[0], chosen[1], rest[0], rest[1]];
+      all.sort_unstable();
+      assert_eq!(all, [0, 1, 2, 3]);
+      counts[chosen[0]][chosen[1]] += 1;
+    }
+    for (a, row) in counts.iter().enumerate() {
+      for (b, &c) in row.iter().enumerate() {
+        if a == b { assert_eq!(c, 0); } else { assert!((c as i32 - 1_000).abs() < 150, "{:?}", counts); }
+      }
+    }
+
+    // k draws: the first step only depends on `index`, and step k-1 on `index + k - 1`
+    let mut items = [0, 1, 2, 3, 4, 5];
+    let (chosen, _) = partial_shuffle(key(7), 40, &mut items, 3);
+    assert_eq!(chosen[0], bounded(key(7), 40, 6) as i32);
+    let mut full = [0, 1, 2];
+    let (chosen, rest) = partial_shuffle(key(7), 0, &mut full, 10);
+    assert_eq!((chosen.len(), rest.len()), (3, 0));
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn derangements_uniform() {
+    use crate::key;
+    use super::{derange, NoDerangement};
+
+    // the 9 derangements of 4 elements, each expected 1_000 times
+    let mut seen = std::collections::BTreeMap::new();
+    for index in 0..9_000 {
+      let mut items = [0, 1, 2, 3];
+      derange(key(6), index * 100, &mut items).unwrap();
+      assert!(items.iter().enumerate().all(|(i, &x)| i != x), "{:?}", items);
+      *seen.entry(items).or_insert(0) += 1;
+    }
+    assert_eq!(seen.len(), 9);
+    assert!(seen.values().all(|&c: &i32| (c - 1_000).abs() < 150), "{:?}", seen);
+
+    let mut long: [usize; 50] = core::array::from_fn(|i| i);
+    derange(key(6), 1, &mut long).unwrap();
+    assert!(long.iter().enumerate().all(|(i, &x)| i != x));
+    let mut again: [usize; 50] = core::array::from_fn(|i| i);
+    derange(key(6), 1, &mut again).unwrap();
+    assert_eq!(long, again);
+
+    let mut pair = ['a', 'b'];
+    derange(key(6), 0, &mut pair).unwrap();
+    assert_eq!(pair, ['b', 'a']);
+    assert_eq!(derange::<u8>(key(6), 0, &mut []), Ok(()));
+    assert_eq!(derange(key(6), 0, &mut [1]), Err(NoDerangement));
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn choose_multiple_distinct_and_reproducible() {
+    use crate::key;
+    use super::choose_multiple;
+
+    let items: [u32; 50] = core::array::from_fn(|i| i as u32);
+    let k = key(3);
+
+    for start in 0..100 {
+      let chosen = choose_multiple(&items, k, start * 20, 20);
+      assert_eq!(chosen.len(), 20);
+      for (i, a) in chosen.iter().enumerate() {
+        assert!(!chosen[i + 1..].contains(a), "{} repeated in {:?}", a, chosen);
+      }
+      assert_eq!(chosen, choose_multiple(&items, k, start * 20, 20));
+    }
+
+    let mut all = choose_multiple(&items, k, 0, 80);
+    assert_eq!(all.len(), items.len());
+    all.sort();
+    assert_eq!(all, items);
+
+    assert!(choose_multiple(&items, k, 0, 0).is_empty());
+    assert!(choose_multiple::<u32>(&[], k, 0, 3).is_empty());
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn weighted_reservoir_favours_heavy_items() {
+    use crate::key;
+    use super::WeightedReservoir;
+
+    let k = key(6);
+    let mut heavy = 0;
+    let mut light = 0;
+    let runs = 400;
+    for run in 0..runs {
+      let mut r = WeightedReservoir::new(10, k, run * 1_000);
+      // ids below 10 weigh 20x the other 990
+      for id in 0..1_000 {
+        r.offer(id, if id < 10 { 20.0 } else { 1.0 });
+      }
+      assert_eq!(r.sample().len(), 10);
+      heavy += r.sample().iter().filter(|&&id| id < 10).count();
+      light += r.sample().iter().filter(|&&id| id >= 10).count();
+    }
+    // a heavy item is retained far more often than a light one
+    let heavy_rate = heavy as f64 / (runs as f64 * 10.0);
+    let light_rate = light as f64 / (runs as f64 * 990.0);
+    assert!(heavy_rate > 10.0 * light_rate, "{} vs {}", heavy_rate, light_rate);
+
+    let sample = |start| {
+      let mut r = WeightedReservoir::new(5, k, start);
+      for id in 0..100 { r.offer(id, (id % 7) as f64); }
+      r.sample().to_vec()
+    };
+    assert_eq!(sample(3), sample(3));
+    assert!(sample(3).iter().all(|id| id % 7 != 0));
+
+    let mut short = WeightedReservoir::new(5, k, 0);
+    short.offer(1, 1.0);
+    short.offer(2, 0.5);
+    assert_eq!(short.sample(), &[1, 2]);
+  }
+}
@@ -1,27 +1,4 @@
-use crate::Key;
-
-/// exchange the u32 halves of a u64
-const fn swap(x: u64) -> u64 {
-  (x >> 32) | (x << 32)
-}
-
-/// perform `x * x + o`
-const fn sq(x: u64, o: u64) -> u64 {
-  x.wrapping_mul(x).wrapping_add(o)
-}
-
-/// one round of Squares
-const fn round(x: u64, o: u64) -> u64 {
-  swap(sq(x, o))
-}
-
-/// initial step of all squares variants
-const fn init(key: Key, counter: u64) -> (u64, u64, u64) {
-  let k = key.inner();
-  let x = counter.wrapping_mul(k);
-  let z = x.wrapping_add(k);
-  (x, x, z)
-}
+use crate::{primitives::{init, round, sq, swap}, Inadmissible, Key};
 
 /// Produces a `u32` of random bits.
 /// Less efficient than [`squares::u64`].
@@ -36,6 +13,21 @@ pub const fn u32(key: Key, index: u64) -> u32 {
   (sq(x, z) >> 32) as u32
 }
 
+/// Produces a `u32` of random bits with two rounds instead of three: cheaper, but of *lower quality*.
+///
+/// Meant for hash-table probing and pre-screening, where speed matters more than statistical quality.
+/// It still passes the per-bit bias and counter-bit avalanche audits of [`analysis`](crate#analysis)
+/// (see the `u32_fast_*` tests there), but it has less margin than [`u32()`], whose stream it does not share.
+#[must_use] #[inline(always)]
+pub const fn u32_fast(key: Key, index: u64) -> u32 {
+  let (x, y, z) = init(key, index);
+
+  let x = round(x, y);
+  let x = round(x, z);
+
+  (sq(x, y) >> 32) as u32
+}
+
 /// Produces a `u64` of random bits.
 #[must_use] #[inline(always)]
 pub const fn u64(key: Key, index: u64) -> u64 {
@@ -49,3 +41,295 @@ pub const fn u64(key: Key, index: u64) -> u64 {
 
   t ^ (sq(x, y) >> 32)
 }
+
+/// A research variant of [`u64()`] with `R` rounds (`2..=6`, checked at compile time), for measuring
+/// the quality/speed tradeoff.
+///
+/// Rounds alternate the offsets `y, z, y, ...` of [`init`](crate::primitives::init), and the output
+/// step continues the alternation. `u64_rounds::<3>` is therefore exactly [`u64()`]; every other `R` is
+/// a different, unvetted stream. `R = 2` still passes the `analysis` bit-bias and avalanche audits
+/// (only a single round fails them), so those audits alone are no argument for dropping a round.
+#[must_use] #[inline(always)]
+pub const fn u64_rounds<const R: u32>(key: Key, index: u64) -> u64 {
+  const { assert!(R >= 2 && R <= 6, "u64_rounds: R must be in 2..=6") };
+  let (mut x, y, z) = init(key, index);
+
+  let mut r = 0;
+  while r < R {
+    x = round(x, if r.is_multiple_of(2) { y } else { z });
+    r += 1;
+  }
+  let (o1, o2) = if R.is_multiple_of(2) { (y, z) } else { (z, y) };
+  let t = sq(x, o1);
+
+  t ^ (sq(swap(t), o2) >> 32)
+}
+
+/// A round count for [`u64_quality`], for choosing generator strength from configuration.
+///
+/// Every level is at least the canonical three rounds, so none is weaker than [`u64()`]:
+/// the extra rounds of `Balanced` and `Strong` buy safety margin against flaws the audits cannot see,
+/// at roughly a third more cost per round. Note they are *different streams*, not stronger versions
+/// of the same one, so changing the level changes every output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Quality {
+  /// 3 rounds: exactly [`u64()`]
+  #[default]
+  Fast,
+  /// 4 rounds: [`u64_rounds::<4>`](u64_rounds)
+  Balanced,
+  /// 5 rounds: [`u64_rounds::<5>`](u64_rounds)
+  Strong,
+}
+
+/// [`u64()`] with the round count picked at runtime by `q`; see [`Quality`] for the mapping.
+#[must_use] #[inline(always)]
+pub const fn u64_quality(key: Key, index: u64, q: Quality) -> u64 {
+  match q {
+    Quality::Fast => u64(key, index),
+    Quality::Balanced => u64_rounds::<4>(key, index),
+    Quality::Strong => u64_rounds::<5>(key, index),
+  }
+}
+
+/// Validates a raw `key` (see [`Key::checked`]), then produces [`u64()`] at `index`.
+/// For callers holding unvalidated keys outside hot paths; otherwise validate once and keep the [`Key`].
+#[inline]
+pub const fn checked_u64(key: u64, index: u64) -> Result<u64, Inadmissible> {
+  match Key::checked(key) {
+    Ok(k) => Ok(u64(k, index)),
+    Err(e) => Err(e)
+  }
+}
+
+/// A fixed admissible key for examples, benchmarks and [`reproducibility_vector`].
+/// Public, so never use it for anything that must be unpredictable.
+pub const EXAMPLE_KEY: Key = Key::unchecked(0xaf9ed4c87b8e4fa5);
+
+/// `u64(EXAMPLE_KEY, i)` for `i` in `0..8`.
+///
+/// These values are a stability contract: they change only in a release that
+/// deliberately versions the algorithm. Pin them in your own tests to detect such an upgrade:
+///
+/// ```
+/// assert_eq!(squares::reproducibility_vector()[0], 0xfad095629bb517ff);
+/// ```
+#[must_use]
+pub const fn reproducibility_vector() -> [u64; 8] {
+  u64_array(EXAMPLE_KEY, 0)
+}
+
+/// the key used to derive sub-counters
+const DERIVE_KEY: Key = Key::unchecked(0xd5c5d87a2e576b81);
+
+/// Derives the counter of the `k`-th draw belonging to `index`.
+/// Draw `0` is `index` itself, later draws land at pseudo-random counters far from `index`,
+/// so functions needing several draws per index don't overlap neighbouring indices.
+///
+/// Frozen: `derive(index, k)` is `u64(Key::unchecked(0xd5c5d87a2e576b81), index).wrapping_add(k)`
+/// for `k > 0`. Every sampler in this crate that takes more than one draw per index builds on it.
+#[must_use] #[inline(always)]
+pub const fn derive(index: u64, k: u64) -> u64 {
+  if k == 0 { return index; }
+  u64(DERIVE_KEY, index).wrapping_add(k)
+}
+
+/// Stateless rejection sampling: attempt `k` (from `0`) evaluates `f(u64(key, derive(index, k)))`,
+/// and the first `Some` is returned with the number of attempts used (from `1`).
+/// Returns `None` if all `max_attempts` attempts are rejected.
+///
+/// Writing samplers on top of this keeps their counter scheme the one documented here,
+/// so refactoring them cannot silently move their draws. Samplers taking several draws per
+/// attempt, and `const fn`s, use [`derive`] directly.
+#[inline]
+pub fn retry_until<T, F: Fn(u64) -> Option<T>>(key: Key, index: u64, max_attempts: u32, f: F) -> Option<(T, u32)> {
+  (0..max_attempts).find_map(|k| f(u64(key, derive(index, k as u64))).map(|v| (v, k + 1)))
+}
+
+/// Produces a uniformly distributed `f32` in `[0, 1)` from the upper 24 bits of [`u32()`].
+#[must_use] #[inline(always)]
+pub fn f32(key: Key, index: u64) -> f32 {
+  (u32(key, index) >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+}
+
+/// Produces a uniformly distributed `f64` in `[0, 1)` from the upper 53 bits of [`u64()`].
+#[must_use] #[inline(always)]
+pub fn f64(key: Key, index: u64) -> f64 {
+  unit_f64(u64(key, index))
+}
+
+/// The upper 53 bits of `bits` as a fraction in `[0, 1)`, the conversion [`f64()`] applies to a draw
+#[must_use] #[inline(always)]
+pub(crate) fn unit_f64(bits: u64) -> f64 {
+  (bits >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Produces a uniformly distributed `f64` in the open interval `(0, 1)`:
+/// the upper 53 bits of [`u64()`], offset by half a step. Never `0.0` or `1.0`, so safe to take logarithms of.
+#[must_use] #[inline(always)]
+pub fn f64_open(key: Key, index: u64) -> f64 {
+  ((u64(key, index) >> 11) as f64 + 0.5) * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Produces `N` consecutive `u64`s: `u64(key, start_index + i)` for `i` in `0..N`.
+#[must_use] #[inline(always)]
+pub const fn u64_array<const N: usize>(key: Key, start_index: u64) -> [u64; N] {
+  let mut out = [0; N];
+  let mut i = 0;
+  while i < N {
+    out[i] = u64(key, start_index.wrapping_add(i as u64));
+    i += 1;
+  }
+  out
+}
+
+/// Produces `N` consecutive uniform `f64`s in `[0, 1)`, e.g. a precomputed noise table:
+/// [`f64()`]`(key, start_index + i)` for `i` in `0..N`. Not `const`, as float arithmetic isn't.
+#[must_use] #[inline]
+pub fn uniform_table<const N: usize>(key: Key, start_index: u64) -> [f64; N] {
+  core::array::from_fn(|i| f64(key, start_index.wrapping_add(i as u64)))
+}
+
+/// Fills `out` with `u64(key, start_index + i)` at position `i`.
+#[inline(always)]
+pub fn fill_u64(key: Key, start_index: u64, out: &mut [u64]) {
+  for (i, x) in out.iter_mut().enumerate() {
+    *x = u64(key, start_index.wrapping_add(i as u64));
+  }
+}
+
+/// Fills `out` with the byte stream of `u64(key, start_index..)`, each word little-endian.
+/// A trailing partial word uses the low bytes of the next output.
+/// Consumes `out.len().div_ceil(8)` indices.
+#[inline(always)]
+pub fn fill_bytes(key: Key, start_index: u64, out: &mut [u8]) {
+  let mut chunks = out.chunks_exact_mut(8);
+  let mut index = start_index;
+  for chunk in &mut chunks {
+    chunk.copy_from_slice(&u64(key, index).to_le_bytes());
+    index = index.wrapping_add(1);
+  }
+  // zip rather than slicing, so no length check is left for the tail
+  let tail = u64(key, index).to_le_bytes();
+  for (dst, src) in chunks.into_remainder().iter_mut().zip(tail) {
+    *dst = src;
+  }
+}
+
+/// An endless decimated stream: `u64(key, start_index)`, `u64(key, start_index + keep_every)`,
+/// `u64(key, start_index + 2 * keep_every)`, ..., counters wrapping at `u64::MAX`.
+///
+/// Equivalent to stepping through the full stream and keeping every `keep_every`-th output,
+/// without computing the discarded ones.
+///
+/// ## Panics
+/// If `keep_every == 0`.
+pub fn decimated(key: Key, start_index: u64, keep_every: u64) -> impl Iterator<Item = u64> {
+  assert!(keep_every != 0, "decimated: keep_every must be non-zero");
+  core::iter::successors(Some(start_index), move |i| Some(i.wrapping_add(keep_every))).map(move |i| u64(key, i))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{key, Inadmissible, Key};
+  use super::{checked_u64, decimated, derive, fill_bytes, fill_u64, reproducibility_vector, retry_until, u64, u64_array, u64_quality, u64_rounds, uniform_table, Quality};
+
+  #[test]
+  fn reproducibility_vector_frozen() {
+    assert_eq!(reproducibility_vector(), [
+      0xfad095629bb517ff, 0x6ef1187ef3869f3c, 0x85832cd22fb5aa12, 0x2148240eb1968081,
+      0xd15341d0084a0036, 0x542eed3fc21f2748, 0x5e1e4db1e29f7bc3, 0x59719df3186000c8,
+    ]);
+  }
+
+  #[test]
+  fn retry_until_counters() {
+    let k = key(30);
+    // accept the third attempt only
+    let target = u64(k, derive(9, 2));
+    let pick = |d: u64| (d == target).then_some(d ^ 1);
+    assert_eq!(retry_until(k, 9, 10, pick), Some((target ^ 1, 3)));
+    assert_eq!(retry_until(k, 9, 10, pick), retry_until(k, 9, 10, pick));
+
+    // the cap
+    assert_eq!(retry_until(k, 9, 2, pick), None);
+    assert_eq!(retry_until(k, 9, 0, Some), None);
+    assert_eq!(retry_until(k, 9, 1, Some), Some((u64(k, 9), 1)));
+
+    // attempt k is draw k of `derive`
+    let seen = core::cell::RefCell::new(std::vec::Vec::new());
+    assert_eq!(retry_until(k, 9, 4, |d| { seen.borrow_mut().push(d); None::<()> }), None);
+    assert_eq!(seen.into_inner(), (0..4).map(|t| u64(k, derive(9, t))).collect::<std::vec::Vec<_>>());
+  }
+
+  #[test]
+  fn quality_levels() {
+    for k in [key(0), key(1)] {
+      for i in [0, 1, u64::MAX] {
+        assert_eq!(u64_quality(k, i, Quality::Fast), u64(k, i));
+        assert_eq!(u64_quality(k, i, Quality::default()), u64(k, i));
+        assert_eq!(u64_quality(k, i, Quality::Balanced), u64_rounds::<4>(k, i));
+        assert_eq!(u64_quality(k, i, Quality::Strong), u64_rounds::<5>(k, i));
+      }
+    }
+  }
+
+  #[test]
+  fn three_rounds_is_canonical() {
+    for k in [key(0), key(1), Key::unchecked(0xaf9ed4c87b8e4fa5)] {
+      for i in [0, 1, 1 << 32, u64::MAX] {
+        assert_eq!(u64_rounds::<3>(k, i), u64(k, i));
+        assert_ne!(u64_rounds::<4>(k, i), u64(k, i));
+      }
+    }
+  }
+
+  #[test]
+  fn decimated_strides() {
+    let k = key(26);
+    let mut it = decimated(k, 7, 3);
+    assert_eq!([it.next(), it.next(), it.next()], [Some(u64(k, 7)), Some(u64(k, 10)), Some(u64(k, 13))]);
+    assert!(decimated(k, 0, 1).take(50).eq((0..50).map(|i| u64(k, i))));
+
+    let wrapped: [u64; 2] = core::array::from_fn(|i| decimated(k, u64::MAX - 1, 1 << 63).nth(i).unwrap());
+    assert_eq!(wrapped, [u64(k, u64::MAX - 1), u64(k, (1 << 63) - 2)]);
+  }
+
+  #[test]
+  fn checked_u64_validates() {
+    let raw = key(3).inner();
+    assert_eq!(checked_u64(raw, 17), Ok(u64(Key::unchecked(raw), 17)));
+    assert_eq!(checked_u64(raw & !0xF, 17), Err(Inadmissible::ContainsZeroNibble { position: 0, nibble: 0 }));
+  }
+
+  #[test]
+  fn uniform_table_matches_f64() {
+    let k = key(4);
+    let table: [f64; 64] = uniform_table(k, u64::MAX - 3);
+    for (i, &v) in table.iter().enumerate() {
+      assert!((0.0..1.0).contains(&v));
+      assert_eq!(v, super::f64(k, (u64::MAX - 3).wrapping_add(i as u64)));
+    }
+    assert_eq!(uniform_table::<0>(k, 0), []);
+  }
+
+  #[test]
+  fn fills_match_u64() {
+    let k = key(4);
+
+    let a: [u64; 7] = u64_array(k, u64::MAX - 3);
+    let mut b = [0; 7];
+    fill_u64(k, u64::MAX - 3, &mut b);
+    assert_eq!(a, b);
+    assert_eq!(a[4], u64(k, 0));
+
+    let mut bytes = [0u8; 53];
+    fill_bytes(k, u64::MAX - 3, &mut bytes);
+    for (i, chunk) in bytes.chunks(8).enumerate() {
+      assert_eq!(chunk, &a[i].to_le_bytes()[..chunk.len()]);
+    }
+
+    fill_bytes(k, 0, &mut []);
+  }
+}
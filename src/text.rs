@@ -0,0 +1,167 @@
+#[cfg(feature = "alloc")] use alloc::string::String;
+use crate::{u64, Key};
+
+/// Scalar-value classes of [`fill_utf8`]: `(weight in percent, first, count)`,
+/// where a class's `count` values are numbered from `first` with the gaps of [`class_scalar`].
+const CLASSES: [(u64, u32, u32); 5] = [
+  // ASCII, controls included
+  (40, 0x0000, 0x80),
+  // combining diacritical marks
+  (10, 0x0300, 0x70),
+  // U+D7F0..=U+D7FF and U+E000..=U+E00F, either side of the surrogates
+  (5, 0xD7F0, 0x20),
+  // the rest of the BMP: no ASCII, the two classes above, or noncharacters
+  (25, 0x0080, 0x1_0000 - 0x80 - 0x70 - 0x820 - 0x20 - 2),
+  // planes 1 to 16, without the two noncharacters ending each plane
+  (20, 0x1_0000, 16 * 0xFFFE),
+];
+
+/// The `k`-th scalar of class `c`, skipping the surrogates and noncharacters the class excludes
+const fn class_scalar(c: usize, k: u32) -> u32 {
+  let first = CLASSES[c].1;
+  match c {
+    2 => if k < 0x10 { first + k } else { 0xE000 + k - 0x10 },
+    3 => {
+      // 0x80..0x300, then 0x370..0xD7F0, then 0xE010..0xFDD0, then 0xFDF0..0xFFFE
+      let cp = first + k;
+      let cp = if cp >= 0x300 { cp + 0x70 } else { cp };
+      let cp = if cp >= 0xD7F0 { cp + 0x820 } else { cp };
+      if cp >= 0xFDD0 { cp + 0x20 } else { cp }
+    }
+    4 => first + (k / 0xFFFE) * 0x1_0000 + k % 0xFFFE,
+    _ => first + k,
+  }
+}
+
+/// The scalar value drawn at `index`: the upper 32 bits of `u64(key, index)` pick a class by weight,
+/// the lower 32 a value within it, each by widening multiply (bias below `2^-12` for the largest class)
+fn scalar(key: Key, index: u64) -> char {
+  let draw = u64(key, index);
+  let mut pick = ((draw >> 32) * 100) >> 32;
+  let mut c = 0;
+  while pick >= CLASSES[c].0 {
+    pick -= CLASSES[c].0;
+    c += 1;
+  }
+  let k = ((draw & 0xFFFF_FFFF) * CLASSES[c].2 as u64) >> 32;
+  // every class excludes the surrogates
+  char::from_u32(class_scalar(c, k as u32)).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Fills `out` with the UTF-8 encoding of as many whole random scalar values as fit, for fuzzing
+/// text parsers; returns the number of bytes written (`0` if even the first does not fit).
+/// Bytes past that are left as they were.
+///
+/// Scalar `s` is drawn at `index + s` from a weighted mix (in percent): ASCII including controls 40,
+/// combining marks U+0300–U+036F 10, the 32 scalars either side of the surrogates 5, the rest of the
+/// Basic Multilingual Plane (none of those) 25, and the supplementary planes 20. Surrogates and noncharacters
+/// (U+FDD0–U+FDEF and the last two of every plane) are never produced.
+pub fn fill_utf8(key: Key, index: u64, out: &mut [u8]) -> usize {
+  let mut len = 0;
+  let mut s = 0u64;
+  loop {
+    let c = scalar(key, index.wrapping_add(s));
+    if len + c.len_utf8() > out.len() { return len; }
+    c.encode_utf8(&mut out[len..]);
+    len += c.len_utf8();
+    s += 1;
+  }
+}
+
+/// `char_count` random scalar values, drawn as by [`fill_utf8`] (feature `alloc`).
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn random_string(key: Key, index: u64, char_count: usize) -> String {
+  (0..char_count as u64).map(|s| scalar(key, index.wrapping_add(s))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::key;
+  use super::{class_scalar, fill_utf8, scalar, CLASSES};
+
+  /// which class a scalar belongs to
+  fn class_of(c: char) -> usize {
+    match c as u32 {
+      0..0x80 => 0,
+      0x300..0x370 => 1,
+      0xD7F0..0xD800 | 0xE000..0xE010 => 2,
+      0x1_0000.. => 4,
+      _ => 3,
+    }
+  }
+
+  #[test]
+  fn class_boundaries() {
+    let excluded = |cp: u32| (0xD800..0xE000).contains(&cp) || (0xFDD0..0xFDF0).contains(&cp) || cp & 0xFFFE == 0xFFFE;
+    for (c, &(_, _, count)) in CLASSES.iter().enumerate() {
+      for k in [0, 1, count / 2, count - 2, count - 1] {
+        let cp = class_scalar(c, k);
+        assert!(!excluded(cp) && cp <= 0x10_FFFF, "{} {} {:x}", c, k, cp);
+      }
+    }
+    assert_eq!(class_scalar(3, 0x300 - 0x80), 0x370);
+    assert_eq!(class_scalar(3, 0xD7F0 - 0x80 - 0x70), 0xE010);
+    assert_eq!(class_scalar(3, CLASSES[3].2 - 1), 0xFFFD);
+    assert_eq!(class_scalar(4, CLASSES[4].2 - 1), 0x10_FFFD);
+    assert_eq!(class_scalar(4, 0xFFFE), 0x2_0000);
+    assert_eq!(class_scalar(2, 0x10), 0xE000);
+
+    // the classes are disjoint
+    for (c, &(_, _, count)) in CLASSES.iter().enumerate().take(4) {
+      for k in (0..count).step_by(if cfg!(miri) { 97 } else { 1 }) {
+        let cp = class_scalar(c, k);
+        assert_eq!(class_of(char::from_u32(cp).unwrap()), c, "{:x}", cp);
+      }
+    }
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn utf8_valid_and_mixed() {
+    let mut counts = [0u32; 5];
+    let mut total = 0;
+    for i in 0..100 {
+      let mut out = [0u8; 1_000];
+      let len = fill_utf8(key(45), i * 1_000, &mut out);
+      assert!(len > 1_000 - 4);
+      let text = core::str::from_utf8(&out[..len]).unwrap();
+      for c in text.chars() {
+        counts[class_of(c)] += 1;
+        total += 1;
+      }
+    }
+    for (c, &(weight, _, _)) in CLASSES.iter().enumerate() {
+      let observed = counts[c] as f64 / total as f64;
+      assert!((observed - weight as f64 / 100.0).abs() < 0.01, "{:?}", counts);
+    }
+  }
+
+  #[test]
+  fn utf8_small_buffers() {
+    let k = key(45);
+    assert_eq!(fill_utf8(k, 0, &mut []), 0);
+    // an index whose first scalar needs more than one byte
+    let wide = (0..).find(|&i| scalar(k, i).len_utf8() > 1).unwrap();
+    let mut one = [0xAA];
+    assert_eq!(fill_utf8(k, wide, &mut one), 0);
+    assert_eq!(one, [0xAA]);
+
+    // a prefix that fits, leaving the rest alone
+    let mut out = [0xAA; 9];
+    let len = fill_utf8(k, 3, &mut out);
+    assert!(len > 9 - 4 && core::str::from_utf8(&out[..len]).is_ok());
+    assert!(out[len..].iter().all(|&b| b == 0xAA));
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn random_string_matches_fill() {
+    use super::random_string;
+    let s = random_string(key(45), 3, 50);
+    assert_eq!(s.chars().count(), 50);
+    let mut out = [0u8; 400];
+    let len = fill_utf8(key(45), 3, &mut out);
+    assert!(core::str::from_utf8(&out[..len]).unwrap().starts_with(&s));
+  }
+}
@@ -0,0 +1,111 @@
+//! `Vec`-returning conveniences over the slice primitives (feature `alloc`).
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+use crate::{bounded, choose_multiple, fill_bytes, fill_u64, Key};
+
+/// `len` outputs `u64(key, start_index..)`, as [`fill_u64`] would write them.
+///
+/// ```
+/// # use squares::{key, random_vec_u64};
+/// let words = random_vec_u64(key(0), 0, 4);
+/// assert_eq!(words[2], squares::u64(key(0), 2));
+/// ```
+#[must_use]
+pub fn random_vec_u64(key: Key, start_index: u64, len: usize) -> Vec<u64> {
+  let mut out = vec![0; len];
+  fill_u64(key, start_index, &mut out);
+  out
+}
+
+/// `len` bytes of the stream at `start_index`, as [`fill_bytes`] would write them.
+#[must_use]
+pub fn random_bytes_vec(key: Key, start_index: u64, len: usize) -> Vec<u8> {
+  let mut out = vec![0; len];
+  fill_bytes(key, start_index, &mut out);
+  out
+}
+
+/// The values of `range` in a uniformly random order: [`choose_multiple`] of all of them,
+/// consuming indices `index..index + range.len()`.
+///
+/// ```
+/// # use squares::{key, shuffled_vec};
+/// let mut order = shuffled_vec(key(0), 0, 0..52);
+/// order.sort_unstable();
+/// assert!(order.iter().copied().eq(0..52));
+/// ```
+///
+/// ## Panics
+/// If the range is too long to collect.
+#[must_use]
+pub fn shuffled_vec(key: Key, index: u64, range: Range<u64>) -> Vec<u64> {
+  let values: Vec<u64> = range.collect();
+  choose_multiple(&values, key, index, values.len())
+}
+
+/// `k` distinct values drawn uniformly from `0..n`, in ascending order (all of `0..n` if `k >= n`).
+///
+/// Uses Floyd's algorithm, so `n` may be huge: draw `s` is [`bounded`] at `index + s`, for `s` in `0..k`.
+#[must_use]
+pub fn sample_vec(key: Key, index: u64, n: u64, k: usize) -> Vec<u64> {
+  let k = (k as u64).min(n);
+  let mut chosen: Vec<u64> = Vec::with_capacity(k as usize);
+
+  for (s, j) in (n - k..n).enumerate() {
+    let t = bounded(key, index.wrapping_add(s as u64), j + 1);
+    // `j` is larger than everything chosen so far, so it always goes last
+    match chosen.binary_search(&t) {
+      Ok(_) => chosen.push(j),
+      Err(at) => chosen.insert(at, t),
+    }
+  }
+  chosen
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{choose_multiple, fill_bytes, key, u64};
+  use super::{random_bytes_vec, random_vec_u64, sample_vec, shuffled_vec};
+
+  #[test]
+  fn vecs_match_slices() {
+    let k = key(17);
+    let words = random_vec_u64(k, u64::MAX, 3);
+    assert_eq!(words, [u64(k, u64::MAX), u64(k, 0), u64(k, 1)]);
+
+    let mut bytes = [0; 21];
+    fill_bytes(k, 5, &mut bytes);
+    assert_eq!(random_bytes_vec(k, 5, 21), bytes);
+
+    let items: [u64; 10] = core::array::from_fn(|i| 100 + i as u64);
+    assert_eq!(shuffled_vec(k, 9, 100..110), choose_multiple(&items, k, 9, 10));
+
+    assert!(random_vec_u64(k, 0, 0).is_empty());
+    assert!(random_bytes_vec(k, 0, 0).is_empty());
+    assert!(shuffled_vec(k, 0, 5..5).is_empty());
+    assert!(sample_vec(k, 0, 10, 0).is_empty());
+    assert!(sample_vec(k, 0, 0, 3).is_empty());
+  }
+
+  #[test]
+  fn sample_vec_distinct() {
+    let k = key(17);
+    assert_eq!(sample_vec(k, 0, 6, 10), [0, 1, 2, 3, 4, 5]);
+
+    let mut hits = [0u32; 20];
+    for index in 0..(if cfg!(miri) { 20 } else { 2_000 }) {
+      let s = sample_vec(k, index * 5, 20, 5);
+      assert_eq!(s.len(), 5);
+      assert!(s.windows(2).all(|w| w[0] < w[1]), "{:?}", s);
+      for x in s { hits[x as usize] += 1; }
+    }
+    if !cfg!(miri) {
+      // each value is chosen with probability 1/4: 500 of 2000
+      assert!(hits.iter().all(|&h| (h as i32 - 500).abs() < 100), "{:?}", hits);
+    }
+
+    let huge = sample_vec(k, 0, u64::MAX, 4);
+    assert!(huge.windows(2).all(|w| w[0] < w[1]));
+  }
+}
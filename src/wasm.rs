@@ -0,0 +1,108 @@
+//! # JavaScript bindings
+//!
+//! `wasm-bindgen` exports (feature `wasm-bindings`) producing the same streams as the Rust API,
+//! for front-end code that must agree with a backend.
+//!
+//! Keys and indices cross as `BigInt` (`u64`); `u53` gives integers that are exact as a JS `Number`.
+
+use wasm_bindgen::prelude::*;
+use crate::Key;
+
+/// A random admissible key for `index`, as [`key`](crate::key).
+#[wasm_bindgen]
+pub fn key(index: u64) -> u64 { crate::key(index).inner() }
+
+/// A uniform integer in `[0, 2^53)`: the upper 53 bits of [`u64()`](crate::u64), exact as a `Number`.
+#[wasm_bindgen]
+pub fn u53(key: u64, index: u64) -> f64 { (crate::u64(Key::unchecked(key), index) >> 11) as f64 }
+
+/// A uniform `f64` in `[0, 1)`, as [`f64()`](crate::f64).
+#[wasm_bindgen]
+pub fn f64(key: u64, index: u64) -> f64 { crate::f64(Key::unchecked(key), index) }
+
+/// A stream of `f64`s in `[0, 1)` under one key: output `i` is `f64(key, index + i)`.
+#[wasm_bindgen]
+pub struct SquaresJs {
+  key: Key,
+  index: u64,
+}
+
+#[wasm_bindgen]
+impl SquaresJs {
+  /// Throws if `key` is not admissible.
+  #[wasm_bindgen(constructor)]
+  pub fn new(key: u64, index: u64) -> Result<SquaresJs, JsError> {
+    let key = Key::checked(key).map_err(|e| JsError::new(e.message()))?;
+    Ok(Self { key, index })
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn key(&self) -> u64 { self.key.inner() }
+
+  #[wasm_bindgen(getter)]
+  pub fn index(&self) -> u64 { self.index }
+
+  pub fn next_f64(&mut self) -> f64 {
+    let x = crate::f64(self.key, self.index);
+    self.index = self.index.wrapping_add(1);
+    x
+  }
+
+  /// Fills a `Float64Array` with the next `out.length` outputs.
+  pub fn fill(&mut self, out: &mut [f64]) {
+    for x in out {
+      *x = self.next_f64();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{f64, key, u53, SquaresJs};
+
+  /// `(key, index, bits)` rows of `kind` in the known-answer vectors
+  fn vectors(kind: &str) -> impl Iterator<Item = [u64; 3]> + '_ {
+    include_str!("../tests/vectors/kat.txt").lines().filter_map(move |line| {
+      let mut fields = line.split(' ');
+      if fields.next() != Some(kind) { return None; }
+      let mut row = [0; 3];
+      for (r, f) in row.iter_mut().zip(fields) { *r = u64::from_str_radix(f, 16).unwrap(); }
+      Some(row)
+    })
+  }
+
+  #[test]
+  fn bindings_match_vectors() {
+    let mut n = 0;
+    for [k, i, bits] in vectors("f64") {
+      assert_eq!(f64(k, i).to_bits(), bits);
+      let mut rng = SquaresJs::new(k, i).unwrap();
+      assert_eq!(rng.next_f64().to_bits(), bits);
+      n += 1;
+    }
+    for [i, k, _] in vectors("key") {
+      assert_eq!(key(i), k);
+      n += 1;
+    }
+    for [k, i, bits] in vectors("u64") {
+      assert_eq!(u53(k, i), (bits >> 11) as f64);
+      assert!(u53(k, i) < 9007199254740992.0);
+      n += 1;
+    }
+    assert!(n > 0);
+  }
+
+  #[test]
+  fn stream_fills_and_keeps_key() {
+    let k = key(12);
+    // a key too large for a `Number`; natively there is no `BigInt` boundary, tests/wasm.rs crosses it
+    assert!(k > 1 << 53);
+    let mut rng = SquaresJs::new(k, u64::MAX).unwrap();
+    assert_eq!(rng.key(), k);
+
+    let mut out = [0.0; 3];
+    rng.fill(&mut out);
+    assert_eq!(out, [f64(k, u64::MAX), f64(k, 0), f64(k, 1)]);
+    assert_eq!(rng.index(), 2);
+  }
+}
@@ -0,0 +1,59 @@
+//! Drives the `squares-keygen` binary (feature `cli`).
+
+use std::process::Command;
+use squares::{key, key_set, verify_pairwise_distinct, Key};
+
+fn keygen(args: &[&str]) -> std::process::Output {
+  Command::new(env!("CARGO_BIN_EXE_squares-keygen")).args(args).output().expect("failed to run squares-keygen")
+}
+
+fn parse_keys(stdout: &[u8]) -> Vec<Key> {
+  String::from_utf8_lossy(stdout).lines()
+    .map(|l| Key::checked(u64::from_str_radix(l, 16).unwrap()).expect("emitted an inadmissible key"))
+    .collect()
+}
+
+#[test]
+fn generates_keys() {
+  let out = keygen(&["--count", "100", "--start-index", "5", "--format", "hex"]);
+  assert!(out.status.success());
+
+  let keys = parse_keys(&out.stdout);
+  assert_eq!(keys.len(), 100);
+  for (i, k) in keys.iter().enumerate() {
+    assert_eq!(k.inner(), key(5 + i as u64).inner());
+  }
+
+  let out = keygen(&["--count", "3", "--format", "dec"]);
+  let dec: Vec<u64> = String::from_utf8_lossy(&out.stdout).lines().map(|l| l.parse().unwrap()).collect();
+  assert_eq!(dec, [key(0).inner(), key(1).inner(), key(2).inner()]);
+}
+
+#[test]
+fn verifies_keys() {
+  let out = keygen(&["--verify", "0xaf9ed4c87b8e4fa5"]);
+  assert!(out.status.success());
+
+  // first nibble is even
+  let out = keygen(&["--verify", "af9ed4c87b8e4fa2"]);
+  assert_eq!(out.status.code(), Some(1));
+  let stderr = String::from_utf8_lossy(&out.stderr);
+  assert!(stderr.contains("First nibble must be odd"), "{}", stderr);
+  assert!(stderr.contains("FirstNibbleEven"), "{}", stderr);
+
+  let out = keygen(&["--verify", "not-hex"]);
+  assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn distinct_keys() {
+  let out = keygen(&["--count", "500", "--distinct"]);
+  assert!(out.status.success());
+
+  let keys = parse_keys(&out.stdout);
+  assert_eq!(verify_pairwise_distinct(&keys), Ok(()));
+
+  let mut expected = vec![Key::unchecked(0); 500];
+  key_set(0, &mut expected);
+  assert!(keys.iter().zip(&expected).all(|(a, b)| a.inner() == b.inner()));
+}
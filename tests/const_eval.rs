@@ -0,0 +1,66 @@
+//! Evaluates the public `const fn`s at compile time.
+//!
+//! If any of them stops being `const`, or its compile-time answer drifts from the known-answer
+//! vectors, this file fails to build. The runtime tests compare the same answers against runtime
+//! evaluation, which is what Miri checks on other targets (see the README's Testing section).
+
+use squares::{bounded, key, Inadmissible, Key};
+
+const K0: Key = key(0);
+const K_MAX: Key = key(u64::MAX);
+const K_HIGH: Key = key(1 << 63);
+
+const _: () = assert!(K0.inner() == 0x1dfd8627d5f26481);
+const _: () = assert!(K_MAX.inner() == 0x441e33d58db72fa1);
+const _: () = assert!(K_HIGH.inner() == 0x3a738e76cb718a2d);
+const _: () = assert!(Key::with_index(7).inner() == 0x8f453bb6e74cfdb1);
+
+const U32_0: u32 = squares::u32(K0, 0);
+const U32_HIGH: u32 = squares::u32(K_MAX, 1 << 63);
+const U64_0: u64 = squares::u64(K0, 0);
+const U64_MAX: u64 = squares::u64(K0, u64::MAX);
+
+const _: () = assert!(U32_0 == 0x1cfbcaf0);
+const _: () = assert!(U32_HIGH == 0x053f6dd9);
+const _: () = assert!(U64_0 == 0x1cfbcaf0a52c9cd6);
+const _: () = assert!(U64_MAX == 0x7c702fe44619f237);
+
+const CHECKED: Result<Key, Inadmissible> = Key::checked(0xaf9ed4c87b8e4fa5);
+const ZERO_NIBBLE: Result<Key, Inadmissible> = Key::checked(0xaf9ed4c87b8e4f05);
+const EVEN_FIRST: Result<Key, Inadmissible> = Key::checked(0xaf9ed4c87b8e4fa2);
+
+const _: () = assert!(CHECKED.is_ok());
+const _: () = assert!(matches!(ZERO_NIBBLE, Err(Inadmissible::ContainsZeroNibble { position: 1, nibble: 0 })));
+const _: () = assert!(matches!(EVEN_FIRST, Err(Inadmissible::FirstNibbleEven { nibble: 2 })));
+
+const BOUNDED_WIDE: u64 = bounded(K0, 0, 1 << 40);
+const BOUNDED_SMALL: u64 = bounded(K0, 1, 10);
+const BOUNDED_HUGE: u64 = bounded(K0, 2, u64::MAX - 1);
+
+const _: () = assert!(BOUNDED_WIDE == 0x1cfbcaf0a5);
+const _: () = assert!(BOUNDED_SMALL == 7);
+const _: () = assert!(BOUNDED_HUGE == 0xcc761bd9a640f539);
+
+#[test]
+fn runtime_matches_const() {
+  let index = std::hint::black_box(0u64);
+  assert_eq!(key(index).inner(), K0.inner());
+  assert_eq!(squares::u32(K0, index), U32_0);
+  assert_eq!(squares::u64(K0, index), U64_0);
+  assert_eq!(squares::u64(K0, index.wrapping_sub(1)), U64_MAX);
+  assert_eq!(Key::checked(std::hint::black_box(0xaf9ed4c87b8e4f05)).err(), ZERO_NIBBLE.err());
+  assert_eq!(bounded(K0, index + 1, 10), BOUNDED_SMALL);
+}
+
+/// Every value here depends on bits above 32 of an index, draw, or nibble-shuffle word.
+/// An accidental `as usize` on a 64-bit quantity would change these on 32-bit targets,
+/// which `cargo miri test --target i686-unknown-linux-gnu` exercises.
+#[test]
+fn wide_values_on_narrow_usize() {
+  let high = std::hint::black_box(1u64 << 63);
+  assert_eq!(key(high).inner(), K_HIGH.inner());
+  assert_eq!(key(high.wrapping_sub(1).wrapping_mul(2).wrapping_add(1)).inner(), K_MAX.inner());
+  assert_eq!(squares::u32(K_MAX, high), U32_HIGH);
+  assert_eq!(bounded(K0, 0, std::hint::black_box(1 << 40)), BOUNDED_WIDE);
+  assert_eq!(bounded(K0, 2, std::hint::black_box(u64::MAX - 1)), BOUNDED_HUGE);
+}
@@ -0,0 +1,38 @@
+//! Host-side tests of the cycle arithmetic in the `embedded-bench` example.
+
+#[path = "../examples/embedded-bench/src/harness.rs"]
+mod harness;
+
+use core::cell::Cell;
+use harness::{cycles_between, cycles_per_call, measure};
+
+#[test]
+fn deltas_wrap() {
+  assert_eq!(cycles_between(100, 250), 150);
+  assert_eq!(cycles_between(u32::MAX - 9, 20), 30);
+  assert_eq!(cycles_between(7, 7), 0);
+}
+
+#[test]
+fn per_call_rounds_and_subtracts_overhead() {
+  assert_eq!(cycles_per_call(10_500, 500, 1_000), 10);
+  assert_eq!(cycles_per_call(11_000, 500, 1_000), 11);
+  assert_eq!(cycles_per_call(10_999, 500, 1_000), 10);
+  assert_eq!(cycles_per_call(400, 500, 1_000), 0);
+  assert_eq!(cycles_per_call(400, 0, 0), 0);
+  assert_eq!(cycles_per_call(u32::MAX, 0, 1), u32::MAX);
+}
+
+#[test]
+fn measure_with_fake_counter() {
+  // each call of `f` costs 7 "cycles" and each reading 3, which the overhead pass cancels;
+  // starting near wraparound
+  let now = Cell::new(u32::MAX - 1_000);
+  let tick = |n: u32| now.set(now.get().wrapping_add(n));
+  let mut seen = Vec::new();
+
+  let read = || { let t = now.get(); tick(3); t };
+  let cycles = measure(read, 500, |i| { seen.push(i); tick(7); });
+  assert_eq!(cycles, 7);
+  assert_eq!(seen, (0..500).collect::<Vec<u64>>());
+}
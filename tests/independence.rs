@@ -0,0 +1,68 @@
+//! Statistical evidence that sibling streams from each derivation mechanism look independent:
+//! cross-correlation at small lags, and no shared outputs beyond chance.
+#![cfg(all(feature = "analysis", feature = "alloc"))]
+
+use squares::{analysis::{cross_correlation, stream_overlap}, key, key_for_domain, key_set, Key};
+
+/// lags checked around each alignment
+const LAGS: i64 = 16;
+/// output pairs per lag
+const SAMPLES: u64 = if cfg!(miri) { 200 } else { 20_000 };
+/// bound on `max_abs_z` over the 33 lags: exceeded by independent streams with probability ~2e-4
+const MAX_Z: f64 = 4.5;
+/// block length for partitioning one key's counter space
+const BLOCK: i64 = 1 << 32;
+
+/// sibling keys from each key-derivation mechanism
+fn sibling_keys() -> [(&'static str, Key, Key); 4] {
+  let mut set = [Key::unchecked(0); 2];
+  key_set(1_000, &mut set);
+  [
+    ("key(i), key(i + 1)", key(41), key(42)),
+    ("key_for_domain", key_for_domain(7, b"physics"), key_for_domain(7, b"render")),
+    ("key_set", set[0], set[1]),
+    ("key(i), key(2^63 + i)", key(3), key(3 | 1 << 63)),
+  ]
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn sibling_keys_uncorrelated() {
+  for (mechanism, a, b) in sibling_keys() {
+    let report = cross_correlation(a, b, -LAGS..LAGS + 1, SAMPLES);
+    assert!(report.max_abs_z() < MAX_Z, "{}: streams correlated, max |z| = {}", mechanism, report.max_abs_z());
+  }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn partitioned_blocks_uncorrelated() {
+  let k = key(5);
+  let report = cross_correlation(k, k, BLOCK - LAGS..BLOCK + LAGS + 1, SAMPLES);
+  assert!(report.max_abs_z() < MAX_Z, "block partitioning: blocks correlated, max |z| = {}", report.max_abs_z());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn detectors_catch_overlap() {
+  // a stream against itself lights up exactly at lag 0, and a shifted copy shares outputs
+  let k = key(5);
+  let report = cross_correlation(k, k, -3..4, SAMPLES);
+  assert!((report.correlation(0) - 1.0).abs() < 1e-9);
+  assert!(report.correlation(1).abs() * (SAMPLES as f64).sqrt() < MAX_Z);
+  assert!(report.max_abs_z() > 100.0);
+
+  let overlap = stream_overlap(&[(k, 0), (k, 1_000)], 2_000);
+  assert!(overlap.observed >= 1_000, "{:?}", overlap);
+  assert!(overlap.z_score > 100.0, "{:?}", overlap);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn no_shared_outputs() {
+  let per_stream = if cfg!(miri) { 100 } else { 100_000 };
+  for (mechanism, a, b) in sibling_keys() {
+    let report = stream_overlap(&[(a, 0), (b, 0), (a, BLOCK as u64), (b, BLOCK as u64)], per_stream);
+    assert!(report.z_score < MAX_Z, "{}: streams share outputs, {:?}", mechanism, report);
+  }
+}
@@ -0,0 +1,33 @@
+//! Known-answer tests: every output mapping must match the committed fixture bit-for-bit,
+//! on every target.
+
+#[path = "vectors/generate.rs"]
+mod generate;
+
+const VECTORS: &str = include_str!("vectors/kat.txt");
+const INTERKEY_COLLISIONS: &str = include_str!("vectors/interkey_collisions.txt");
+
+#[test]
+fn known_answers() {
+  let expected = VECTORS.replace("\r\n", "\n");
+  let actual = generate::generate();
+
+  for (n, (e, a)) in expected.lines().zip(actual.lines()).enumerate() {
+    assert_eq!(e, a, "tests/vectors/kat.txt line {} differs", n + 1);
+  }
+  assert_eq!(expected.lines().count(), actual.lines().count(), "tests/vectors/kat.txt length differs");
+}
+
+/// `key()` collisions under the inter-key rule are a property of the keygen, frozen like any output
+#[cfg_attr(miri, ignore)]
+#[test]
+fn known_interkey_collisions() {
+  let expected = INTERKEY_COLLISIONS.replace("\r\n", "\n");
+  assert_eq!(expected, generate::interkey_collisions());
+
+  for line in expected.lines().filter(|l| !l.starts_with('#')) {
+    let mut fields = line.split_whitespace().map(|f| f.parse::<u64>().unwrap());
+    let (a, b) = (fields.next().unwrap(), fields.next().unwrap());
+    assert!(!squares::keys_distinct(squares::key(a), squares::key(b)), "{}", line);
+  }
+}
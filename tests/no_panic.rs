@@ -0,0 +1,102 @@
+//! Link-time proof that the core generation paths cannot panic.
+//!
+//! Each wrapper is annotated with `#[no_panic]`, which fails the *link* if the optimizer
+//! cannot remove every panic path from the function body. Only meaningful with optimizations:
+//!
+//! ```sh
+//! cargo test --release --all-features --test no_panic
+//! ```
+#![cfg(not(debug_assertions))]
+
+use no_panic::no_panic;
+use squares::{Key, key, key_for_domain, key_from_bytes, keys_distinct};
+
+#[no_panic]
+fn u32(key: Key, index: u64) -> u32 { squares::u32(key, index) }
+
+#[no_panic]
+fn u32_fast(key: Key, index: u64) -> u32 { squares::u32_fast(key, index) }
+
+#[no_panic]
+fn u64(key: Key, index: u64) -> u64 { squares::u64(key, index) }
+
+#[no_panic]
+fn f32(key: Key, index: u64) -> f32 { squares::f32(key, index) }
+
+#[no_panic]
+fn f64(key: Key, index: u64) -> f64 { squares::f64(key, index) }
+
+#[no_panic]
+fn u64_array(key: Key, index: u64) -> [u64; 16] { squares::u64_array(key, index) }
+
+#[no_panic]
+fn fill_u64(key: Key, index: u64, out: &mut [u64]) { squares::fill_u64(key, index, out) }
+
+#[no_panic]
+fn fill_bytes(key: Key, index: u64, out: &mut [u8]) { squares::fill_bytes(key, index, out) }
+
+#[no_panic]
+fn make_key(index: u64) -> Key { key(index) }
+
+#[no_panic]
+fn make_key_from_bytes(bytes: &[u8]) -> Key { key_from_bytes(bytes) }
+
+#[no_panic]
+fn make_key_for_domain(index: u64, domain: &[u8]) -> Key { key_for_domain(index, domain) }
+
+#[no_panic]
+fn check(key: u64) -> bool { Key::checked(key).is_ok() }
+
+#[no_panic]
+fn distinct(a: Key, b: Key) -> bool { keys_distinct(a, b) }
+
+#[test]
+fn generation_paths_link() {
+  // the wrappers must be used for the linker to see them
+  let k = make_key(std::hint::black_box(u64::MAX));
+  let mut words = [0; 5];
+  let mut bytes = [0; 13];
+  fill_u64(k, u64::MAX, &mut words);
+  fill_bytes(k, u64::MAX, &mut bytes);
+
+  assert_eq!(words[..], u64_array(k, u64::MAX)[..5]);
+  assert_eq!(bytes[..8], u64(k, u64::MAX).to_le_bytes());
+  assert_eq!(u32(k, 0), squares::u32(k, 0));
+  assert_eq!(u32_fast(k, 0), squares::u32_fast(k, 0));
+  assert!((0.0..1.0).contains(&f32(k, 0)) && (0.0..1.0).contains(&f64(k, 0)));
+  assert!(check(k.inner()) && !check(std::hint::black_box(0)));
+  assert!(distinct(make_key_from_bytes(b"a"), make_key_for_domain(0, b"a")));
+}
+
+#[cfg(all(feature = "rand", feature = "rand_09"))]
+#[test]
+fn rng_paths_cannot_panic() {
+  use squares::Squares;
+
+  #[no_panic]
+  fn skip(rng: Squares, n: u64) -> Squares { rng.skip(n) }
+
+  #[no_panic]
+  fn jump(rng: &mut Squares, n: u64) { rng.jump(n) }
+
+  #[no_panic]
+  fn fill_06(rng: &mut Squares, out: &mut [u8]) { rand_core::RngCore::fill_bytes(rng, out) }
+
+  #[no_panic]
+  fn fill_09(rng: &mut Squares, out: &mut [u8]) { rand_core_09::RngCore::fill_bytes(rng, out) }
+
+  #[no_panic]
+  fn next_06(rng: &mut Squares) -> u64 { rand_core::RngCore::next_u64(rng) }
+
+  #[no_panic]
+  fn next_09(rng: &mut Squares) -> u32 { rand_core_09::RngCore::next_u32(rng) }
+
+  let mut rng = skip(Squares::with_key(key(0)), u64::MAX);
+  jump(&mut rng, 1);
+  let mut bytes = [0; 13];
+  fill_06(&mut rng, &mut bytes);
+  fill_09(&mut rng, &mut bytes);
+  next_06(&mut rng);
+  next_09(&mut rng);
+  assert_eq!(rng.index(), 6);
+}
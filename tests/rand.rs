@@ -0,0 +1,50 @@
+//! `Squares` as seen from a dependent crate: built from public constructors and driven through `RngCore`.
+#![cfg(feature = "rand")]
+
+use rand::RngCore;
+use squares::{Key, Squares};
+
+#[test]
+fn construct_and_draw() {
+  let mut rng = Squares::with_key(Key::with_index(7));
+  let k = Key::with_index(7);
+  assert_eq!(rng.next_u64(), squares::u64(k, 0));
+  assert_eq!(rng.next_u64(), squares::u64(k, 1));
+  assert_eq!(rng.index(), 2);
+  assert_eq!(rng.key().inner(), k.inner());
+}
+
+#[test]
+fn fill_bytes_is_the_word_stream() {
+  let k = Key::with_index(7);
+  let mut rng = Squares::with_key(k).with_index(100);
+  let mut buf = [0u8; 20];
+  rng.fill_bytes(&mut buf);
+
+  let mut expected = [0u8; 24];
+  for (i, chunk) in expected.chunks_exact_mut(8).enumerate() {
+    chunk.copy_from_slice(&squares::u64(k, 100 + i as u64).to_le_bytes());
+  }
+  assert_eq!(buf, expected[..20]);
+}
+
+#[test]
+fn skip_and_logged_identity_reproduce() {
+  let mut rng = Squares::with_key(Key::with_index(3)).skip(10);
+  assert_eq!(rng.index(), 10);
+  rng.next_u64();
+
+  let (key, index) = (rng.key(), rng.index());
+  let mut replay = Squares::with_key(key).with_index(index);
+  for _ in 0..8 {
+    assert_eq!(rng.next_u64(), replay.next_u64());
+  }
+}
+
+#[test]
+fn usable_as_dyn_rng_core() {
+  fn draw(rng: &mut dyn RngCore) -> u64 { rng.next_u64() }
+
+  let mut rng = Squares::with_key(Key::with_index(7));
+  assert_eq!(draw(&mut rng), squares::u64(Key::with_index(7), 0));
+}
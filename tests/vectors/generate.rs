@@ -0,0 +1,239 @@
+//! Generates the known-answer vectors in `tests/vectors/kat.txt`,
+//! and the known inter-key collisions in `tests/vectors/interkey_collisions.txt`.
+//!
+//! Shared (via `#[path]`) by the `kat` test, which compares against the committed file,
+//! and the `regen_vectors` example, which rewrites it.
+
+use std::fmt::Write;
+use squares::{key, Key};
+
+/// keys the vectors are generated under
+fn keys() -> [Key; 4] {
+  [key(0), key(1), key(u64::MAX), Key::unchecked(0xaf9ed4c87b8e4fa5)]
+}
+
+/// counters the vectors are generated at
+const INDICES: [u64; 8] = [0, 1, 2, 3, 0xffff_ffff, 1 << 32, 0x8000_0000_0000_0000, u64::MAX];
+
+/// byte lengths of the `fill_bytes` vectors
+const FILL_LENS: [usize; 6] = [1, 4, 5, 8, 13, 32];
+
+/// Renders every vector, one per line.
+pub fn generate() -> String {
+  let mut s = String::new();
+  writeln!(s, "# squares known-answer vectors").unwrap();
+  writeln!(s, "# regenerate (only when deliberately versioning a construction) with:").unwrap();
+  writeln!(s, "#   cargo run --example regen_vectors").unwrap();
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "u32 {:016x} {:016x} {:08x}", k.inner(), i, squares::u32(k, i)).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "u32_fast {:016x} {:016x} {:08x}", k.inner(), i, squares::u32_fast(k, i)).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "u64 {:016x} {:016x} {:016x}", k.inner(), i, squares::u64(k, i)).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      let r = [
+        squares::u64_rounds::<2>(k, i), squares::u64_rounds::<3>(k, i), squares::u64_rounds::<4>(k, i),
+        squares::u64_rounds::<5>(k, i), squares::u64_rounds::<6>(k, i),
+      ];
+      for (n, v) in (2..).zip(r) {
+        writeln!(s, "u64_rounds {} {:016x} {:016x} {:016x}", n, k.inner(), i, v).unwrap();
+      }
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "f32 {:016x} {:016x} {:08x}", k.inner(), i, squares::f32(k, i).to_bits()).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "f64 {:016x} {:016x} {:016x}", k.inner(), i, squares::f64(k, i).to_bits()).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in INDICES {
+      writeln!(s, "f64_open {:016x} {:016x} {:016x}", k.inner(), i, squares::f64_open(k, i).to_bits()).unwrap();
+    }
+  }
+
+  for i in INDICES {
+    let k = key(0);
+    writeln!(s, "exponential {:016x} {:016x} 2.5 {:016x}", k.inner(), i, squares::exponential(k, i, 2.5).to_bits()).unwrap();
+    writeln!(s, "weibull {:016x} {:016x} 0.5 3 {:016x}", k.inner(), i, squares::weibull(k, i, 0.5, 3.0).to_bits()).unwrap();
+  }
+
+  for (a, b) in [(0.5, 0.5), (2.0, 5.0), (0.3, 3.0)] {
+    for i in INDICES {
+      let k = key(0);
+      writeln!(s, "beta {:016x} {:016x} {} {} {:016x}", k.inner(), i, a, b, squares::beta(k, i, a, b).to_bits()).unwrap();
+    }
+  }
+
+  for rho in [-0.8, 0.5] {
+    for i in INDICES {
+      let k = key(0);
+      let (u, v) = squares::correlated_pair(k, i, rho);
+      writeln!(s, "correlated_pair {:016x} {:016x} {} {:016x} {:016x}", k.inner(), i, rho, u.to_bits(), v.to_bits()).unwrap();
+    }
+  }
+
+  for (population, successes, draws) in [(20, 7, 9), (1_000, 400, 300), (1_000_000, 999_000, 700_000)] {
+    for i in INDICES {
+      let k = key(0);
+      let x = squares::hypergeometric(k, i, population, successes, draws);
+      writeln!(s, "hypergeometric {:016x} {:016x} {} {} {} {}", k.inner(), i, population, successes, draws, x).unwrap();
+    }
+  }
+
+  for i in INDICES.into_iter().chain([7, 239482304, 0x0123_4567_89ab_cdef]) {
+    writeln!(s, "key {:016x} {:016x}", i, key(i).inner()).unwrap();
+  }
+
+  for name in [&b""[..], b"a", b"overworld", b"the quick brown fox jumps"] {
+    let hex: String = name.iter().map(|b| format!("{:02x}", b)).collect();
+    let hex = if hex.is_empty() { "-".into() } else { hex };
+    writeln!(s, "key_from_bytes {} {:016x}", hex, squares::key_from_bytes(name).inner()).unwrap();
+  }
+
+  for seeds in [&[][..], &[0], &[1, 2], &[2, 1]] {
+    let list: Vec<String> = seeds.iter().map(|x: &u64| format!("{:x}", x)).collect();
+    let list = if list.is_empty() { "-".into() } else { list.join(",") };
+    writeln!(s, "key_from_seeds {} {:016x}", list, squares::key_from_seeds(seeds).inner()).unwrap();
+  }
+
+  for (domain, i) in [("billing", 0), ("billing", 1), ("search", 0)] {
+    writeln!(s, "key_for_domain {} {:016x} {:016x}", domain, i, squares::key_for_domain(i, domain.as_bytes()).inner()).unwrap();
+  }
+
+  for i in INDICES {
+    let id: String = squares::uuid_bytes(key(0), i).iter().map(|b| format!("{:02x}", b)).collect();
+    writeln!(s, "uuid_bytes {:016x} {:016x} {}", key(0).inner(), i, id).unwrap();
+  }
+
+  for i in INDICES {
+    let mut out = [0; 3];
+    let n = squares::weighted_sample_without_replacement(key(0), i, &[0.5, 1.0, 0.0, 2.0, 3.0, 0.25], 3, &mut out);
+    let chosen: Vec<String> = out[..n].iter().map(|c| c.to_string()).collect();
+    writeln!(s, "weighted_sample_without_replacement {:016x} {:016x} 0.5,1,0,2,3,0.25 {}", key(0).inner(), i, chosen.join(",")).unwrap();
+  }
+
+  for i in INDICES {
+    let logits = [0.5, -1.0, f64::NEG_INFINITY, 2.0, 0.0];
+    let mut out = [0; 3];
+    let n = squares::gumbel_top_k(key(0), i, &logits, 3, &mut out);
+    let chosen: Vec<String> = out[..n].iter().map(|c| c.to_string()).collect();
+    let max = squares::gumbel_max(key(0), i, &logits).unwrap();
+    writeln!(s, "gumbel {:016x} {:016x} 0.5,-1,-inf,2,0 {} {}", key(0).inner(), i, max, chosen.join(",")).unwrap();
+  }
+
+  for i in [0, u64::MAX - 254] {
+    let table: String = squares::random_sbox(key(0), i).0.iter().map(|b| format!("{:02x}", b)).collect();
+    writeln!(s, "random_sbox {:016x} {:016x} {}", key(0).inner(), i, table).unwrap();
+  }
+
+  for i in [0, u64::MAX] {
+    let path: Vec<String> = squares::BrownianPath::new(key(0), 0.25, i).take(4).map(|x| format!("{:016x}", x.to_bits())).collect();
+    writeln!(s, "brownian_path {:016x} {:016x} 0.25 {}", key(0).inner(), i, path.join(",")).unwrap();
+    let x = squares::brownian_bridge(key(0), i, 0.0, 2.0, -1.0, 3.0, 0.5);
+    writeln!(s, "brownian_bridge {:016x} {:016x} 0 2 -1 3 0.5 {:016x}", key(0).inner(), i, x.to_bits()).unwrap();
+  }
+
+  for i in INDICES {
+    let k = key(0);
+    let rr = squares::randomized_response(k, i, true, 0.75) as u8;
+    writeln!(s, "laplace {:016x} {:016x} 1.5 {:016x} randomized_response true 0.75 {}", k.inner(), i, squares::laplace(k, i, 1.5).to_bits(), rr).unwrap();
+  }
+
+  for i in [0, u64::MAX - 7] {
+    let mut out = [0; 24];
+    let len = squares::fill_utf8(key(0), i, &mut out);
+    let bytes: String = out[..len].iter().map(|b| format!("{:02x}", b)).collect();
+    writeln!(s, "fill_utf8 {:016x} {:016x} 24 {}", key(0).inner(), i, bytes).unwrap();
+  }
+
+  for i in INDICES {
+    for tag in ["", "hp roll", "loot seed"] {
+      writeln!(s, "attribute {:016x} {:016x} {:?} {:016x}", key(0).inner(), i, tag, squares::attribute(key(0), i, tag)).unwrap();
+    }
+  }
+
+  for bits in [1, 63, 64, 65, 130] {
+    let mut limbs = [0; 3];
+    squares::random_bits(key(0), 5, bits, &mut limbs);
+    writeln!(s, "random_bits {:016x} {:016x} {} {:016x} {:016x} {:016x}", key(0).inner(), 5, bits, limbs[0], limbs[1], limbs[2]).unwrap();
+  }
+
+  for modulus in [&[7][..], &[u64::MAX], &[3, 1], &[0, 0, 1]] {
+    for i in [0, u64::MAX] {
+      let mut out = [0; 3];
+      squares::random_below(key(0), i, modulus, &mut out);
+      let m: Vec<String> = modulus.iter().map(|l| format!("{:x}", l)).collect();
+      writeln!(s, "random_below {:016x} {:016x} {} {:016x} {:016x} {:016x}", key(0).inner(), i, m.join(","), out[0], out[1], out[2]).unwrap();
+    }
+  }
+
+  for k in keys() {
+    for i in [0, u64::MAX - 4] {
+      let mut out = [[0; 3]; 6];
+      squares::palette(k, i, out.len(), &mut out);
+      let colors: Vec<String> = out.iter().map(|c| format!("{:02x}{:02x}{:02x}", c[0], c[1], c[2])).collect();
+      writeln!(s, "palette {:016x} {:016x} {}", k.inner(), i, colors.join(",")).unwrap();
+    }
+  }
+
+  let [a, b, c, d] = keys();
+  let bundle = squares::KeyBundle4::new([a, b, c, d]).unwrap();
+  for i in [0, u64::MAX] {
+    let bytes: String = squares::block256(&bundle, i).iter().map(|b| format!("{:02x}", b)).collect();
+    writeln!(s, "block256 {:016x},{:016x},{:016x},{:016x} {:016x} {}", a.inner(), b.inner(), c.inner(), d.inner(), i, bytes).unwrap();
+  }
+
+  for k in keys() {
+    for (len, i) in FILL_LENS.into_iter().zip([0, 1, 2, 3, 1 << 32, u64::MAX - 4]) {
+      let mut out = vec![0; len];
+      squares::fill_bytes(k, i, &mut out);
+      let bytes: String = out.iter().map(|b| format!("{:02x}", b)).collect();
+      writeln!(s, "fill_bytes {:016x} {:016x} {} {}", k.inner(), i, len, bytes).unwrap();
+    }
+  }
+
+  s
+}
+
+/// indices `0..INTERKEY_SEARCH` are searched exhaustively for inter-key collisions
+const INTERKEY_SEARCH: u64 = 100_000;
+
+/// Every pair of indices below [`INTERKEY_SEARCH`] whose keys share their lower 9 nibbles,
+/// as `<index a> <index b> <shared lower 9 nibbles>`, sorted by the shared value.
+pub fn interkey_collisions() -> String {
+  let mut lower: Vec<(u64, u64)> = (0..INTERKEY_SEARCH)
+    .map(|i| (key(i).inner() & ((1 << 36) - 1), i))
+    .collect();
+  lower.sort_unstable();
+
+  let mut s = String::new();
+  writeln!(s, "# key() index pairs in 0..{} violating the inter-key rule", INTERKEY_SEARCH).unwrap();
+  for w in lower.windows(2) {
+    if w[0].0 == w[1].0 {
+      writeln!(s, "{} {} {:09x}", w[0].1, w[1].1, w[0].0).unwrap();
+    }
+  }
+  s
+}
@@ -0,0 +1,65 @@
+//! The JavaScript bindings under wasm-bindgen-test: `wasm-pack test --node -- --features wasm-bindings --test wasm`.
+//! Values are bounced through JavaScript so keys and indices really cross as `BigInt`.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm-bindings"))]
+
+use squares::wasm::{f64, key, u53, SquaresJs};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen(inline_js = "export function echo(x) { if (typeof x !== 'bigint') throw new TypeError(typeof x); return x; }")]
+extern "C" {
+  /// hands a `u64` to JavaScript and back, throwing unless it arrived as a `BigInt`
+  fn echo(x: u64) -> u64;
+}
+
+/// `(key, index, bits)` rows of `kind` in the known-answer vectors
+fn vectors(kind: &str) -> impl Iterator<Item = [u64; 3]> + '_ {
+  include_str!("vectors/kat.txt").lines().filter_map(move |line| {
+    let mut fields = line.split(' ');
+    if fields.next() != Some(kind) { return None; }
+    let mut row = [0; 3];
+    for (r, f) in row.iter_mut().zip(fields) { *r = u64::from_str_radix(f, 16).unwrap(); }
+    Some(row)
+  })
+}
+
+#[wasm_bindgen_test]
+fn bindings_match_vectors() {
+  let mut n = 0;
+  for [k, i, bits] in vectors("f64") {
+    assert_eq!(f64(echo(k), echo(i)).to_bits(), bits);
+    let mut rng = SquaresJs::new(echo(k), echo(i)).unwrap();
+    assert_eq!(rng.next_f64().to_bits(), bits);
+    n += 1;
+  }
+  for [i, k, _] in vectors("key") {
+    assert_eq!(echo(key(echo(i))), k);
+    n += 1;
+  }
+  for [k, i, bits] in vectors("u64") {
+    assert_eq!(u53(echo(k), echo(i)), (bits >> 11) as f64);
+    n += 1;
+  }
+  assert!(n > 0);
+}
+
+#[wasm_bindgen_test]
+fn bigint_round_trips() {
+  assert_eq!(echo(u64::MAX), u64::MAX);
+  assert_eq!(echo(1 << 53 | 1), 1 << 53 | 1);
+
+  let k = key(12);
+  assert!(k > 1 << 53);
+  let mut rng = SquaresJs::new(echo(k), echo(u64::MAX)).unwrap();
+  assert_eq!(echo(rng.key()), k);
+  assert_eq!(echo(rng.index()), u64::MAX);
+
+  let mut out = [0.0; 3];
+  rng.fill(&mut out);
+  assert_eq!(out, [f64(k, u64::MAX), f64(k, 0), f64(k, 1)]);
+  assert_eq!(echo(rng.index()), 2);
+
+  // an inadmissible key throws
+  assert!(SquaresJs::new(echo(u64::MAX), 0).is_err());
+}